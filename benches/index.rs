@@ -8,10 +8,7 @@ pub fn add_index_benchmark(c: &mut Criterion) {
         .lines()
         .filter(|l| !l.is_empty())
         .enumerate()
-        .map(|(index, line)| Document {
-            id: index as u64,
-            text: line.to_string(),
-        })
+        .map(|(index, line)| Document::new(index as u64, line.to_string()))
         .collect::<Vec<Document>>();
 
     c.bench_function("index-large-text", |b| {
@@ -22,15 +19,28 @@ pub fn add_index_benchmark(c: &mut Criterion) {
     });
 }
 
-pub fn search_index_benchmark(c: &mut Criterion) {
+pub fn add_parallel_index_benchmark(c: &mut Criterion) {
     let docs = TEXT
         .lines()
         .filter(|l| !l.is_empty())
         .enumerate()
-        .map(|(index, line)| Document {
-            id: index as u64,
-            text: line.to_string(),
+        .map(|(index, line)| Document::new(index as u64, line.to_string()))
+        .collect::<Vec<Document>>();
+
+    c.bench_function("index-large-text-parallel", |b| {
+        b.iter(|| {
+            let mut index = InvertedIndex::default();
+            index.add_parallel(&docs)
         })
+    });
+}
+
+pub fn search_index_benchmark(c: &mut Criterion) {
+    let docs = TEXT
+        .lines()
+        .filter(|l| !l.is_empty())
+        .enumerate()
+        .map(|(index, line)| Document::new(index as u64, line.to_string()))
         .collect::<Vec<Document>>();
 
     let mut index = InvertedIndex::default();
@@ -39,5 +49,10 @@ pub fn search_index_benchmark(c: &mut Criterion) {
     c.bench_function("search-in-index", |b| b.iter(|| index.search("make peace")));
 }
 
-criterion_group!(benches, add_index_benchmark, search_index_benchmark);
+criterion_group!(
+    benches,
+    add_index_benchmark,
+    add_parallel_index_benchmark,
+    search_index_benchmark
+);
 criterion_main!(benches);
@@ -1,11 +1,18 @@
 /// The analyzer combines the tokenizer and all filters and applies them to a text.
-use crate::filters::{Filters, Language};
-use crate::tokenizer::Tokenizer;
+use crate::case_fold::CaseFoldFilter;
+use crate::char_filters::{CharFilter, FilteredText};
+use crate::cjk::CjkBigramTokenizer;
+use crate::filters::{Language, StemmingFilter, StopWordsFilter, TokenFilter};
+use crate::lemmatizer::LemmatizerFilter;
+use crate::normalize::{NormalizationFilter, NormalizationForm};
+use crate::tokenizer::{Token, Tokenize, Tokenizer};
+use std::collections::HashSet;
 
 /// `Analyzer` tokenize and applies filters to a text.
 pub struct Analyzer {
-    tokenizer: Tokenizer,
-    filters: Filters,
+    char_filters: Vec<Box<dyn CharFilter>>,
+    tokenizer: Box<dyn Tokenize>,
+    filters: Vec<Box<dyn TokenFilter>>,
 }
 
 /// `Default` assume that a text will be in English.
@@ -17,26 +24,96 @@ impl Default for Analyzer {
 
 impl Analyzer {
     /// Creates an analyzer with custom language.
+    /// It applies tokenizer, NFC normalization, case folding, stop words, and stemming filters.
+    /// Chinese, Japanese and Korean text is split into bigrams by `CjkBigramTokenizer`.
     pub fn new(language: Language) -> Self {
+        Self::with_reduction(language, Box::new(StemmingFilter::new(&language)))
+    }
+
+    /// Creates an analyzer with custom language which lemmatizes words instead of stemming.
+    /// Stemming is applied only to unknown words if the lemmatizer has a stemming fallback.
+    pub fn with_lemmatizer(language: Language, lemmatizer: LemmatizerFilter) -> Self {
+        Self::with_reduction(language, Box::new(lemmatizer))
+    }
+
+    /// Creates a language analyzer where `reduction` reduces words to their base form.
+    fn with_reduction(language: Language, reduction: Box<dyn TokenFilter>) -> Self {
+        let tokenizer: Box<dyn Tokenize> = if language.is_cjk() {
+            Box::new(CjkBigramTokenizer::new())
+        } else {
+            Box::new(Tokenizer::new())
+        };
+        Analyzer {
+            char_filters: Vec::new(),
+            tokenizer,
+            filters: vec![
+                Box::new(NormalizationFilter::new(NormalizationForm::Nfc)),
+                Box::new(CaseFoldFilter::new(&language)),
+                Box::new(StopWordsFilter::new(&language)),
+                reduction,
+            ],
+        }
+    }
+
+    /// Creates an analyzer with custom tokenizer and chain of filters.
+    /// Filters are applied in the given order.
+    pub fn custom<T>(tokenizer: T, filters: Vec<Box<dyn TokenFilter>>) -> Self
+    where
+        T: Tokenize + 'static,
+    {
         Analyzer {
-            tokenizer: Tokenizer::new(),
-            filters: Filters::new(language),
+            char_filters: Vec::new(),
+            tokenizer: Box::new(tokenizer),
+            filters,
         }
     }
 
-    /// Applies tokenizer, lowercase, stop words, and stemming filters.
+    /// Sets char filters, they are applied in the given order before the tokenizer.
+    pub fn with_char_filters(mut self, char_filters: Vec<Box<dyn CharFilter>>) -> Self {
+        self.char_filters = char_filters;
+        self
+    }
+
+    /// Applies char filters, tokenizer and all filters, returns every produced token
+    /// with its position and offset in the original text.
+    pub fn token_stream(&self, text: &str) -> Vec<Token> {
+        let mut filtered: Vec<FilteredText> = Vec::with_capacity(self.char_filters.len());
+        for char_filter in &self.char_filters {
+            let input = filtered.last().map_or(text, |f| f.text.as_str());
+            filtered.push(char_filter.filter(input));
+        }
+        let input = filtered.last().map_or(text, |f| f.text.as_str());
+        let tokens = self.tokenizer.tokens(input);
+        let mut tokens = self
+            .filters
+            .iter()
+            .fold(tokens, |tokens, filter| filter.filter(tokens));
+        for token in &mut tokens {
+            for f in filtered.iter().rev() {
+                token.offset = f.start_offset(token.offset.start)..f.end_offset(token.offset.end);
+            }
+        }
+        tokens
+    }
+
+    /// Applies tokenizer and all filters, returns distinct terms in order of appearance.
     pub fn analyze(&self, text: &str) -> Vec<String> {
-        let tokens = self.tokenizer.tokenize(text);
-        let low = self.filters.lowercase(tokens);
-        let stopped = self.filters.stop_words(low);
-        self.filters.stemming(stopped).collect()
+        let mut seen = HashSet::new();
+        self.token_stream(text)
+            .into_iter()
+            .map(|t| t.text)
+            .filter(|t| seen.insert(t.clone()))
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod analyzer_tests {
     use crate::analyzer::Analyzer;
-    use crate::filters::Language;
+    use crate::char_filters::{HtmlStripCharFilter, MarkdownStripCharFilter};
+    use crate::filters::{Language, LowercaseFilter};
+    use crate::ngram::EdgeNGramFilter;
+    use crate::tokenizer::Tokenizer;
 
     #[test]
     fn test_analyze() {
@@ -53,4 +130,58 @@ mod analyzer_tests {
         let res: Vec<String> = analyzer.analyze(text);
         assert_eq!(res.len(), 8, "custom lang text analyze failed");
     }
+
+    #[test]
+    fn test_analyze_normalizes() {
+        let analyzer = Analyzer::default();
+        let composed = analyzer.analyze("CAF\u{c9}");
+        let decomposed = analyzer.analyze("cafe\u{301}");
+        assert_eq!(composed, decomposed, "composed and decomposed terms differ");
+    }
+
+    #[test]
+    fn test_analyze_cjk() {
+        let analyzer = Analyzer::new(Language::Chinese);
+        let res: Vec<String> = analyzer.analyze("我们在北京学习Rust");
+        let expected = ["们在", "在北", "北京", "京学", "学习", "rust"];
+        assert_eq!(res, expected, "cjk text analyze failed");
+    }
+
+    #[test]
+    fn test_analyze_custom_chain() {
+        let analyzer = Analyzer::custom(
+            Tokenizer::new(),
+            vec![
+                Box::new(LowercaseFilter),
+                Box::new(EdgeNGramFilter::new(2, 3)),
+            ],
+        );
+        let res: Vec<String> = analyzer.analyze("The Thin fox");
+        let expected = ["th", "the", "thi", "fo", "fox"];
+        assert_eq!(res, expected, "custom chain analyze failed");
+    }
+
+    #[test]
+    fn test_analyze_char_filters() {
+        let analyzer = Analyzer::default().with_char_filters(vec![Box::new(HtmlStripCharFilter)]);
+        let text = "<h1>Fish</h1><p>caf&eacute; <b>menu</b></p>";
+        let res: Vec<String> = analyzer.analyze(text);
+        assert_eq!(res, ["fish", "café", "menu"], "html text analyze failed");
+    }
+
+    #[test]
+    fn test_token_stream_original_offsets() {
+        let analyzer = Analyzer::default().with_char_filters(vec![
+            Box::new(MarkdownStripCharFilter),
+            Box::new(HtmlStripCharFilter),
+        ]);
+        let text = "# Rust\nA **fast** <em>language</em> &amp; [tools](http://x.io)";
+        let res: Vec<&str> = analyzer
+            .token_stream(text)
+            .into_iter()
+            .map(|t| &text[t.offset])
+            .collect();
+        let expected = ["Rust", "fast", "language", "tools"];
+        assert_eq!(res, expected, "offsets in original text failed");
+    }
 }
@@ -0,0 +1,192 @@
+/// Case folding removes case distinctions from a text for caseless matching.
+/// Unlike lowercasing it also unifies characters which only differ in case-like variants,
+/// e.g. German "ß" and "SS" both fold to "ss", Greek final sigma "ς" folds to "σ".
+/// For more details, see https://www.unicode.org/reports/tr44/#CaseFolding.txt
+use crate::filters::{Language, TokenFilter};
+use crate::tokenizer::Token;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Bundled C and F entries of the Unicode CaseFolding.txt, one mapping per line.
+const CASE_FOLDING: &str = include_str!("case_folding.txt");
+
+/// `CaseFoldFilter` applies Unicode full default case folding to tokens.
+/// `turkic`: folds "I" to dotless "ı" and "İ" to "i" as required by Turkish and Azerbaijani
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CaseFoldFilter {
+    pub turkic: bool,
+}
+
+impl CaseFoldFilter {
+    /// Creates a filter for a language, Turkic mode is enabled for Turkish.
+    pub fn new(language: &Language) -> Self {
+        CaseFoldFilter {
+            turkic: matches!(language, Language::Turkish),
+        }
+    }
+
+    /// Folds the case of a text.
+    pub fn fold(&self, text: &str) -> String {
+        let mut res = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                'I' if self.turkic => res.push('ı'),
+                'İ' if self.turkic => res.push('i'),
+                _ => fold_char(c, &mut res),
+            }
+        }
+        res
+    }
+}
+
+impl TokenFilter for CaseFoldFilter {
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+        tokens
+            .into_iter()
+            .map(|t| Token {
+                text: self.fold(&t.text),
+                ..t
+            })
+            .collect()
+    }
+}
+
+/// Appends the case folding of a character.
+/// Characters missing from the table fold to themselves, as in Unicode default case folding.
+fn fold_char(c: char, res: &mut String) {
+    match case_folding().get(&c) {
+        Some(folded) => res.push_str(folded),
+        None => res.push(c),
+    }
+}
+
+/// Returns the bundled case folding table, parsed on first use.
+fn case_folding() -> &'static HashMap<char, String> {
+    static TABLE: OnceLock<HashMap<char, String>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        CASE_FOLDING
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split(';').map(str::trim);
+                let code = parse_code(fields.next()?)?;
+                let _status = fields.next()?;
+                let folded = fields
+                    .next()?
+                    .split(' ')
+                    .map(parse_code)
+                    .collect::<Option<String>>()?;
+                Some((code, folded))
+            })
+            .collect()
+    })
+}
+
+fn parse_code(hex: &str) -> Option<char> {
+    char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+}
+
+#[cfg(test)]
+mod case_fold_tests {
+    use crate::analyzer::Analyzer;
+    use crate::case_fold::CaseFoldFilter;
+    use crate::filters::Language;
+
+    #[test]
+    fn test_fold_sharp_s() {
+        let filter = CaseFoldFilter::default();
+        assert_eq!(
+            filter.fold("Straße"),
+            filter.fold("STRASSE"),
+            "ß folding failed"
+        );
+        assert_eq!(filter.fold("STRAẞE"), "strasse", "capital ß folding failed");
+    }
+
+    #[test]
+    fn test_fold_final_sigma() {
+        let filter = CaseFoldFilter::default();
+        assert_eq!(filter.fold("ΟΔΥΣΣΕΥΣ"), "οδυσσευσ", "sigma folding failed");
+        assert_eq!(
+            filter.fold("Οδυσσεύς"),
+            "οδυσσεύσ",
+            "final sigma folding failed"
+        );
+    }
+
+    #[test]
+    fn test_fold_special() {
+        let filter = CaseFoldFilter::default();
+        assert_eq!(filter.fold("ﬁle"), "file", "ligature folding failed");
+        assert_eq!(filter.fold("ᾼ"), "αι", "iota subscript folding failed");
+        assert_eq!(filter.fold("ꭰᏸ"), "ᎠᏰ", "cherokee folding failed");
+    }
+
+    #[test]
+    fn test_fold_table() {
+        let filter = CaseFoldFilter::default();
+        assert_eq!(
+            filter.fold("5µm"),
+            filter.fold("5μm"),
+            "micro sign folding failed"
+        );
+        assert_eq!(
+            filter.fold("\u{1c80}\u{1c88}"),
+            "\u{432}\u{a64b}",
+            "old Cyrillic folding failed"
+        );
+        assert_eq!(
+            filter.fold("\u{1f50}"),
+            "\u{3c5}\u{313}",
+            "upsilon with psili folding failed"
+        );
+        assert_eq!(
+            filter.fold("\u{1fb6}"),
+            "\u{3b1}\u{342}",
+            "alpha with perispomeni folding failed"
+        );
+        assert_eq!(filter.fold("ǅ"), "ǆ", "titlecase folding failed");
+    }
+
+    #[test]
+    fn test_fold_cherokee() {
+        let filter = CaseFoldFilter::default();
+        assert_eq!(filter.fold("Ꭰ"), filter.fold("ꭰ"), "cherokee cases differ");
+        for text in ["Ꭰ", "ꭰᏸ", "Straße", "ΟΔΥΣΣΕΥΣ", "ﬁle", "İstanbul", "ǅ"] {
+            let folded = filter.fold(text);
+            assert_eq!(filter.fold(&folded), folded, "folding is not idempotent");
+        }
+    }
+
+    #[test]
+    fn test_fold_dotted_i() {
+        let filter = CaseFoldFilter::default();
+        assert_eq!(
+            filter.fold("İstanbul"),
+            "i\u{307}stanbul",
+            "dotted I folding failed"
+        );
+        assert_eq!(filter.fold("DIŞ"), "diş", "dotless I folding failed");
+    }
+
+    #[test]
+    fn test_fold_turkic() {
+        let filter = CaseFoldFilter::new(&Language::Turkish);
+        assert_eq!(
+            filter.fold("İstanbul"),
+            "istanbul",
+            "turkic dotted I folding failed"
+        );
+        assert_eq!(filter.fold("DIŞ"), "dış", "turkic dotless I folding failed");
+    }
+
+    #[test]
+    fn test_analyze_folds_case() {
+        let analyzer = Analyzer::new(Language::German);
+        assert_eq!(
+            analyzer.analyze("GROSSE STRASSE"),
+            analyzer.analyze("große Straße"),
+            "analyzer does not fold case"
+        );
+    }
+}
@@ -0,0 +1,433 @@
+/// Char filters clean up a text before it's split into tokens.
+/// `HtmlStripCharFilter` removes HTML tags and decodes entities,
+/// `MarkdownStripCharFilter` removes markdown markup.
+/// Every filter records how offsets in the filtered text map back to the original text,
+/// so token offsets still point to the right place of a document, e.g. for highlighting.
+use std::ops::Range;
+
+/// `CharFilter` is implemented by all char filters, so they can be used in an `Analyzer`.
+pub trait CharFilter: Send + Sync {
+    /// Filters text, the result keeps track of offsets in the original text.
+    fn filter(&self, text: &str) -> FilteredText;
+}
+
+/// `FilteredText` is a result of a char filter.
+/// `text`: is a filtered text
+#[derive(Clone, Debug, Default)]
+pub struct FilteredText {
+    pub text: String,
+    spans: Vec<Span>,
+}
+
+/// A part of a filtered text and the part of the original text it was produced from.
+/// Parts of equal length are copies, others are replacements.
+#[derive(Clone, Debug)]
+struct Span {
+    filtered: Range<usize>,
+    original: Range<usize>,
+}
+
+/// `HtmlStripCharFilter` removes HTML tags, comments, scripts and styles and decodes entities.
+/// Block level tags are replaced with a line break, so words around them are not glued together.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HtmlStripCharFilter;
+
+/// `MarkdownStripCharFilter` removes markdown markup and keeps the text.
+/// Headings, quotes and list markers, emphasis, code spans and fences, and link targets
+/// are removed, link texts and image descriptions are kept.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MarkdownStripCharFilter;
+
+impl FilteredText {
+    /// Appends a part of the original text as is.
+    fn keep(&mut self, original: &str, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        let start = self.text.len();
+        self.text.push_str(&original[range.clone()]);
+        if let Some(last) = self.spans.last_mut() {
+            let is_copy = last.filtered.len() == last.original.len();
+            if is_copy && last.filtered.end == start && last.original.end == range.start {
+                last.filtered.end = self.text.len();
+                last.original.end = range.end;
+                return;
+            }
+        }
+        self.spans.push(Span {
+            filtered: start..self.text.len(),
+            original: range,
+        });
+    }
+
+    /// Appends a replacement of a part of the original text, an empty replacement removes it.
+    fn replace(&mut self, range: Range<usize>, replacement: &str) {
+        if replacement.is_empty() {
+            return;
+        }
+        let start = self.text.len();
+        self.text.push_str(replacement);
+        self.spans.push(Span {
+            filtered: start..self.text.len(),
+            original: range,
+        });
+    }
+
+    /// Maps an offset where a token starts in the filtered text to the original text.
+    pub fn start_offset(&self, offset: usize) -> usize {
+        let i = self.spans.partition_point(|s| s.filtered.end <= offset);
+        match self.spans.get(i) {
+            Some(span) if span.filtered.len() == span.original.len() => {
+                span.original.start + (offset - span.filtered.start)
+            }
+            Some(span) => span.original.start,
+            None => self.spans.last().map_or(0, |span| span.original.end),
+        }
+    }
+
+    /// Maps an offset where a token ends in the filtered text to the original text.
+    pub fn end_offset(&self, offset: usize) -> usize {
+        let i = self.spans.partition_point(|s| s.filtered.end < offset);
+        match self.spans.get(i) {
+            Some(span) if span.filtered.len() == span.original.len() => {
+                span.original.start + (offset - span.filtered.start)
+            }
+            Some(span) => span.original.end,
+            None => self.spans.last().map_or(0, |span| span.original.end),
+        }
+    }
+}
+
+impl CharFilter for HtmlStripCharFilter {
+    fn filter(&self, text: &str) -> FilteredText {
+        let mut res = FilteredText::default();
+        let mut copied = 0;
+        let mut i = 0;
+        while let Some(found) = text[i..].find(['<', '&']) {
+            let start = i + found;
+            let Some((end, replacement)) = html_markup(text, start) else {
+                i = start + 1;
+                continue;
+            };
+            res.keep(text, copied..start);
+            res.replace(start..end, &replacement);
+            copied = end;
+            i = end;
+        }
+        res.keep(text, copied..text.len());
+        res
+    }
+}
+
+impl CharFilter for MarkdownStripCharFilter {
+    fn filter(&self, text: &str) -> FilteredText {
+        let mut res = FilteredText::default();
+        let mut in_fence = false;
+        let mut line_start = 0;
+        for line in text.split_inclusive('\n') {
+            let range = line_start..line_start + line.len();
+            line_start = range.end;
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+                res.replace(range, "\n");
+            } else if in_fence {
+                res.keep(text, range);
+            } else if is_link_definition(trimmed) || is_rule(trimmed) {
+                res.replace(range, "\n");
+            } else {
+                let content = range.start + line_prefix_len(line);
+                strip_inline(text, content..range.end, &mut res);
+            }
+        }
+        res
+    }
+}
+
+/// Recognizes a tag, comment or entity at `start`.
+/// Returns where it ends and what it should be replaced with.
+fn html_markup(text: &str, start: usize) -> Option<(usize, String)> {
+    let rest = &text[start..];
+    if rest.starts_with('&') {
+        // Searching bytes, as the limit may fall inside a multibyte character.
+        let end = rest.bytes().take(12).position(|b| b == b';')?;
+        let decoded = decode_entity(&rest[1..end])?;
+        return Some((start + end + 1, decoded.to_string()));
+    }
+    if rest.starts_with("<!--") {
+        let end = rest.find("-->").map_or(text.len(), |end| start + end + 3);
+        return Some((end, String::new()));
+    }
+
+    let name_len = rest[1..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '/' || c == '!'))
+        .unwrap_or(rest.len() - 1);
+    let name = rest[1..1 + name_len]
+        .trim_start_matches('/')
+        .to_ascii_lowercase();
+    if name.is_empty() || !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '!') {
+        return None;
+    }
+    let end = start + rest.find('>')? + 1;
+
+    if (name == "script" || name == "style") && !rest[1..].starts_with('/') {
+        let closing = format!("</{name}");
+        let body = text[end..].to_ascii_lowercase();
+        let close = body.find(&closing).map_or(text.len(), |i| {
+            let tag_end = body[i..].find('>').map_or(body.len(), |j| i + j + 1);
+            end + tag_end
+        });
+        return Some((close, "\n".to_string()));
+    }
+
+    let block = [
+        "address",
+        "article",
+        "aside",
+        "blockquote",
+        "br",
+        "dd",
+        "div",
+        "dl",
+        "dt",
+        "footer",
+        "h1",
+        "h2",
+        "h3",
+        "h4",
+        "h5",
+        "h6",
+        "header",
+        "hr",
+        "li",
+        "nav",
+        "ol",
+        "p",
+        "pre",
+        "section",
+        "table",
+        "td",
+        "th",
+        "title",
+        "tr",
+        "ul",
+    ];
+    let replacement = if block.contains(&name.as_str()) {
+        "\n"
+    } else {
+        ""
+    };
+    Some((end, replacement.to_string()))
+}
+
+/// Decodes a named or numeric entity without the leading `&` and trailing `;`.
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(code) = entity.strip_prefix('#') {
+        let value = match code.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => code.parse().ok()?,
+        };
+        return char::from_u32(value);
+    }
+    let decoded = match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "laquo" => '«',
+        "raquo" => '»',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "euro" => '€',
+        "pound" => '£',
+        "deg" => '°',
+        "middot" => '·',
+        "times" => '×',
+        "aacute" => 'á',
+        "eacute" => 'é',
+        "iacute" => 'í',
+        "oacute" => 'ó',
+        "uacute" => 'ú',
+        "agrave" => 'à',
+        "egrave" => 'è',
+        "auml" => 'ä',
+        "ouml" => 'ö',
+        "uuml" => 'ü',
+        "szlig" => 'ß',
+        "ccedil" => 'ç',
+        "ntilde" => 'ñ',
+        _ => return None,
+    };
+    Some(decoded)
+}
+
+/// Length of heading, quote and list markers at the start of a line.
+fn line_prefix_len(line: &str) -> usize {
+    let mut rest = line;
+    loop {
+        let trimmed = rest.trim_start_matches([' ', '\t']);
+        let marker = if let Some(after) = trimmed.strip_prefix('>') {
+            Some(after)
+        } else if trimmed.starts_with('#') {
+            let after = trimmed.trim_start_matches('#');
+            after.starts_with([' ', '\t', '\n']).then_some(after)
+        } else if let Some(after) = trimmed.strip_prefix(['-', '*', '+']) {
+            after.starts_with([' ', '\t']).then_some(after)
+        } else {
+            let digits = trimmed.trim_start_matches(|c: char| c.is_ascii_digit());
+            let after = digits.strip_prefix(['.', ')']);
+            after.filter(|a| digits.len() < trimmed.len() && a.starts_with([' ', '\t']))
+        };
+        match marker {
+            Some(after) => rest = after,
+            None => return line.len() - trimmed.len(),
+        }
+    }
+}
+
+/// Strips emphasis, code spans, links and images from a part of a line.
+fn strip_inline(text: &str, range: Range<usize>, res: &mut FilteredText) {
+    let line = &text[range.clone()];
+    let mut copied = range.start;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let at = range.start + i;
+        let prev = line[..i].chars().next_back();
+        let next = chars.peek().map(|&(_, c)| c);
+        let remove = match c {
+            '*' | '`' => 1,
+            '~' if next == Some('~') => 2,
+            '_' if !prev.is_some_and(char::is_alphanumeric)
+                || !next.is_some_and(char::is_alphanumeric) =>
+            {
+                1
+            }
+            '!' if next == Some('[') => 1,
+            '[' => 1,
+            ']' if next == Some('(') => {
+                let target = line[i..].find(')').map_or(line.len() - i, |j| j + 1);
+                res.keep(text, copied..at);
+                copied = at + target;
+                while chars.peek().is_some_and(|&(j, _)| j < i + target) {
+                    chars.next();
+                }
+                continue;
+            }
+            ']' => 1,
+            _ => 0,
+        };
+        if remove > 0 {
+            res.keep(text, copied..at);
+            copied = at + remove;
+            if remove == 2 {
+                chars.next();
+            }
+        }
+    }
+    res.keep(text, copied..range.end);
+}
+
+fn is_link_definition(line: &str) -> bool {
+    line.starts_with('[') && line.contains("]:")
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.trim_end();
+    line.len() >= 3
+        && ["-", "*", "_"]
+            .iter()
+            .any(|m| line.split(m).all(|p| p.trim().is_empty()))
+}
+
+#[cfg(test)]
+mod char_filters_tests {
+    use crate::char_filters::{CharFilter, HtmlStripCharFilter, MarkdownStripCharFilter};
+
+    #[test]
+    fn test_html_strip() {
+        let text = "<div class=\"a\">Fish &amp; <b>chips</b></div><p>caf&eacute;&#33;</p>";
+        let res = HtmlStripCharFilter.filter(text);
+        assert_eq!(
+            res.text, "\nFish & chips\n\ncafé!\n",
+            "html stripping failed"
+        );
+    }
+
+    #[test]
+    fn test_html_strip_script_and_comments() {
+        let text = "a<!-- b --><script>var c = 1;</script><style>.d {}</style>e";
+        let res = HtmlStripCharFilter.filter(text);
+        assert_eq!(res.text, "a\n\ne", "script stripping failed");
+    }
+
+    #[test]
+    fn test_html_keeps_plain_text() {
+        let text = "1 < 2 & 3 > 2";
+        let res = HtmlStripCharFilter.filter(text);
+        assert_eq!(res.text, text, "plain text changed");
+    }
+
+    #[test]
+    fn test_html_ampersand_before_multibyte() {
+        for text in ["Tom &ééééééé done", "Мы & Вы Мыйжыйж", "&amp;Мы"] {
+            let res = HtmlStripCharFilter.filter(text);
+            assert_eq!(
+                res.text,
+                text.replace("&amp;", "&"),
+                "ampersand before multibyte text failed"
+            );
+        }
+    }
+
+    #[test]
+    fn test_html_offsets() {
+        let text = "<b>Fish</b> caf&eacute;";
+        let res = HtmlStripCharFilter.filter(text);
+        assert_eq!(res.text, "Fish café");
+        let fish = res.start_offset(0)..res.end_offset(4);
+        assert_eq!(&text[fish], "Fish", "copied text offsets failed");
+        let cafe = res.start_offset(5)..res.end_offset(10);
+        assert_eq!(&text[cafe], "caf&eacute;", "replaced text offsets failed");
+    }
+
+    #[test]
+    fn test_markdown_strip() {
+        let text = "# Title\n> quote with **bold** and _it_\n- item `code` [link](http://x.io)\n";
+        let res = MarkdownStripCharFilter.filter(text);
+        assert_eq!(
+            res.text, "Title\nquote with bold and it\nitem code link\n",
+            "markdown stripping failed"
+        );
+    }
+
+    #[test]
+    fn test_markdown_keeps_identifiers() {
+        let text = "1. call snake_case_name\n```\nlet a = 1;\n```\n---\n";
+        let res = MarkdownStripCharFilter.filter(text);
+        assert_eq!(
+            res.text, "call snake_case_name\n\nlet a = 1;\n\n\n",
+            "markdown stripping failed"
+        );
+    }
+
+    #[test]
+    fn test_markdown_offsets() {
+        let text = "## See [docs](http://x.io) now";
+        let res = MarkdownStripCharFilter.filter(text);
+        assert_eq!(res.text, "See docs now");
+        let docs = res.start_offset(4)..res.end_offset(8);
+        assert_eq!(&text[docs], "docs", "markdown offsets failed");
+        let now = res.start_offset(9)..res.end_offset(12);
+        assert_eq!(&text[now], "now", "markdown offsets failed");
+    }
+}
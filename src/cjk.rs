@@ -0,0 +1,225 @@
+/// CJK tokenizers handle Chinese, Japanese and Korean text, where words are not separated by spaces.
+/// `CjkBigramTokenizer` indexes every pair of adjacent CJK characters and needs no dictionary,
+/// `CjkDictionaryTokenizer` splits CJK text into dictionary words by forward maximum matching.
+/// Text in other scripts is split into words with Unicode Text Segmentation by both of them.
+use crate::tokenizer::{Token, Tokenize};
+use std::collections::HashSet;
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Bundled dictionary of common Chinese words, one word per line.
+const DICTIONARY: &str = include_str!("cjk_dictionary.txt");
+
+/// `CjkBigramTokenizer` converts runs of CJK characters to overlapping bigrams,
+/// a run of a single character is kept as a unigram.
+#[derive(Default)]
+pub struct CjkBigramTokenizer {}
+
+/// `CjkDictionaryTokenizer` converts runs of CJK characters to the longest dictionary words,
+/// characters which don't start any dictionary word become separate tokens.
+pub struct CjkDictionaryTokenizer {
+    words: HashSet<String>,
+    max_chars: usize,
+}
+
+impl CjkBigramTokenizer {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Tokenize for CjkBigramTokenizer {
+    fn tokens(&self, text: &str) -> Vec<Token> {
+        tokenize(text, |run| {
+            let chars = char_bounds(run);
+            if chars.len() == 2 {
+                return chars.windows(2).map(|w| w[0]..w[1]).collect();
+            }
+            chars.windows(3).map(|w| w[0]..w[2]).collect()
+        })
+    }
+}
+
+impl Default for CjkDictionaryTokenizer {
+    fn default() -> Self {
+        Self::bundled()
+    }
+}
+
+impl CjkDictionaryTokenizer {
+    /// Creates a tokenizer with custom dictionary.
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let words: HashSet<String> = words.into_iter().map(Into::into).collect();
+        let max_chars = words.iter().map(|w| w.chars().count()).max().unwrap_or(1);
+        CjkDictionaryTokenizer { words, max_chars }
+    }
+
+    /// Creates a tokenizer with the bundled dictionary of common Chinese words.
+    pub fn bundled() -> Self {
+        Self::new(DICTIONARY.lines().map(str::trim).filter(|w| !w.is_empty()))
+    }
+}
+
+impl Tokenize for CjkDictionaryTokenizer {
+    fn tokens(&self, text: &str) -> Vec<Token> {
+        tokenize(text, |run| {
+            let chars = char_bounds(run);
+            let mut words = Vec::new();
+            let mut start = 0;
+            while start + 1 < chars.len() {
+                let longest = (2..=self.max_chars.min(chars.len() - 1 - start))
+                    .rev()
+                    .find(|&len| self.words.contains(&run[chars[start]..chars[start + len]]))
+                    .unwrap_or(1);
+                words.push(chars[start]..chars[start + longest]);
+                start += longest;
+            }
+            words
+        })
+    }
+}
+
+/// Returns true for Han ideographs, Japanese kana and Korean hangul.
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{1100}'..='\u{11FF}'
+        | '\u{3040}'..='\u{30FF}'
+        | '\u{3130}'..='\u{318F}'
+        | '\u{31F0}'..='\u{31FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FF66}'..='\u{FF9F}'
+        | '\u{20000}'..='\u{2FA1F}'
+    )
+}
+
+/// Splits text into runs of CJK and other characters,
+/// CJK runs are split into byte ranges by `split_run`, other text is split into words.
+fn tokenize<F>(text: &str, split_run: F) -> Vec<Token>
+where
+    F: Fn(&str) -> Vec<Range<usize>>,
+{
+    let mut offsets = Vec::new();
+    let mut start = 0;
+    let mut in_run = false;
+    for (i, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        // Combining marks and variation selectors stay in the run they follow.
+        if i < text.len() && in_run && is_extend(c) {
+            continue;
+        }
+        let cjk = i < text.len() && is_cjk(c);
+        if cjk != in_run || i == text.len() {
+            let chunk = &text[start..i];
+            if in_run {
+                offsets.extend(
+                    split_run(chunk)
+                        .into_iter()
+                        .map(|r| start + r.start..start + r.end),
+                );
+            } else {
+                offsets.extend(
+                    chunk
+                        .unicode_word_indices()
+                        .map(|(j, word)| start + j..start + j + word.len()),
+                );
+            }
+            start = i;
+            in_run = cjk;
+        }
+    }
+
+    offsets
+        .into_iter()
+        .enumerate()
+        .map(|(position, offset)| Token {
+            text: text[offset.clone()].to_string(),
+            position,
+            offset,
+        })
+        .collect()
+}
+
+/// Byte offsets of all graphemes of a text followed by the text length.
+fn char_bounds(text: &str) -> Vec<usize> {
+    text.grapheme_indices(true)
+        .map(|(i, _)| i)
+        .chain(std::iter::once(text.len()))
+        .collect()
+}
+
+fn is_extend(c: char) -> bool {
+    matches!(c, '\u{3099}'..='\u{309A}' | '\u{FE00}'..='\u{FE0F}' | '\u{E0100}'..='\u{E01EF}')
+}
+
+#[cfg(test)]
+mod cjk_tests {
+    use crate::cjk::{CjkBigramTokenizer, CjkDictionaryTokenizer};
+    use crate::tokenizer::{Token, Tokenize};
+
+    fn texts(tokens: Vec<Token>) -> Vec<String> {
+        tokens.into_iter().map(|t| t.text).collect()
+    }
+
+    #[test]
+    fn test_bigram() {
+        let tokenizer = CjkBigramTokenizer::new();
+        let res = texts(tokenizer.tokens("我爱北京天安门"));
+        let expected = ["我爱", "爱北", "北京", "京天", "天安", "安门"];
+        assert_eq!(res, expected, "cjk bigram tokenization failed");
+    }
+
+    #[test]
+    fn test_bigram_mixed_scripts() {
+        let tokenizer = CjkBigramTokenizer::new();
+        let res = tokenizer.tokens("Rust是一种语言, 東京 and ソウル!");
+        let expected = [
+            "Rust", "是一", "一种", "种语", "语言", "東京", "and", "ソウ", "ウル",
+        ];
+        assert_eq!(
+            texts(res.clone()),
+            expected,
+            "mixed text tokenization failed"
+        );
+        assert_eq!(res[2].offset, 7..13, "offsets failed");
+        let positions: Vec<usize> = res.iter().map(|t| t.position).collect();
+        assert_eq!(
+            positions,
+            (0..9).collect::<Vec<usize>>(),
+            "positions failed"
+        );
+    }
+
+    #[test]
+    fn test_bigram_single_char() {
+        let tokenizer = CjkBigramTokenizer::new();
+        let res = texts(tokenizer.tokens("猫 and 犬"));
+        assert_eq!(res, ["猫", "and", "犬"], "single char run is lost");
+    }
+
+    #[test]
+    fn test_dictionary() {
+        let tokenizer = CjkDictionaryTokenizer::new(["北京", "天安门", "我们"]);
+        let res = texts(tokenizer.tokens("我们爱北京天安门"));
+        assert_eq!(
+            res,
+            ["我们", "爱", "北京", "天安门"],
+            "dictionary segmentation failed"
+        );
+    }
+
+    #[test]
+    fn test_bundled_dictionary() {
+        let tokenizer = CjkDictionaryTokenizer::bundled();
+        let res = texts(tokenizer.tokens("中华人民共和国"));
+        assert_eq!(res, ["中华人民共和国"], "bundled dictionary failed");
+    }
+}
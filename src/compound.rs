@@ -0,0 +1,250 @@
+/// Compound filters split tokens made of several words into their parts.
+/// `DictionaryCompoundFilter` finds dictionary words inside compounds of languages like
+/// German and Dutch, so "Donaudampfschiff" matches "Dampfschiff".
+/// `WordDelimiterFilter` splits tokens on punctuation and between letters and digits,
+/// so "wi-fi" matches "wifi" and "wi fi", "SD500" matches "SD 500".
+use crate::filters::TokenFilter;
+use crate::tokenizer::Token;
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// `DictionaryCompoundFilter` adds dictionary words found inside tokens after the token itself.
+/// Subwords share the position of the compound, matching ignores case.
+/// `min_word_size`: is a minimal length of a token to be decomposed in characters
+/// `min_subword_size`: is a minimal length of a subword in characters
+/// `max_subword_size`: is a maximal length of a subword in characters
+/// `only_longest_match`: adds only the longest subword starting at every character
+#[derive(Clone, Debug)]
+pub struct DictionaryCompoundFilter {
+    words: HashSet<String>,
+    pub min_word_size: usize,
+    pub min_subword_size: usize,
+    pub max_subword_size: usize,
+    pub only_longest_match: bool,
+}
+
+/// `WordDelimiterFilter` splits tokens into parts on non-alphanumeric characters,
+/// parts share the position of the token. Tokens without letters and digits are removed.
+/// `split_on_numerics`: splits letters from digits, e.g. "SD500" to "SD" and "500"
+/// `catenate_all`: adds all parts joined together, e.g. "wifi" for "wi-fi"
+/// `preserve_original`: keeps the token itself before its parts
+#[derive(Clone, Copy, Debug)]
+pub struct WordDelimiterFilter {
+    pub split_on_numerics: bool,
+    pub catenate_all: bool,
+    pub preserve_original: bool,
+}
+
+impl DictionaryCompoundFilter {
+    /// Creates a filter with a dictionary of words compounds are made of.
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        DictionaryCompoundFilter {
+            words: words
+                .into_iter()
+                .map(|w| w.as_ref().to_lowercase())
+                .collect(),
+            min_word_size: 5,
+            min_subword_size: 2,
+            max_subword_size: 15,
+            only_longest_match: false,
+        }
+    }
+
+    /// Finds byte ranges of dictionary words inside a word.
+    pub fn decompose(&self, word: &str) -> Vec<Range<usize>> {
+        let bounds: Vec<usize> = word
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(word.len()))
+            .collect();
+        let chars = bounds.len() - 1;
+        let mut res = Vec::new();
+        if chars < self.min_word_size {
+            return res;
+        }
+        for start in 0..chars {
+            let max_len = self.max_subword_size.min(chars - start);
+            let mut found = (self.min_subword_size.max(1)..=max_len)
+                .map(|len| bounds[start]..bounds[start + len])
+                .filter(|r| {
+                    r.len() < word.len() && self.words.contains(&word[r.clone()].to_lowercase())
+                });
+            if self.only_longest_match {
+                res.extend(found.next_back());
+            } else {
+                res.extend(found);
+            }
+        }
+        res
+    }
+}
+
+impl TokenFilter for DictionaryCompoundFilter {
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let mut res = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            let parts = self.decompose(&token.text);
+            let subwords: Vec<Token> = parts.into_iter().map(|r| token.subword(r)).collect();
+            res.push(token);
+            res.extend(subwords);
+        }
+        res
+    }
+}
+
+impl Default for WordDelimiterFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordDelimiterFilter {
+    /// Creates a filter which splits on numerics and keeps the token and its catenation.
+    pub fn new() -> Self {
+        WordDelimiterFilter {
+            split_on_numerics: true,
+            catenate_all: true,
+            preserve_original: true,
+        }
+    }
+
+    /// Splits a token into byte ranges of its parts.
+    pub fn split(&self, text: &str) -> Vec<Range<usize>> {
+        let mut res = Vec::new();
+        let mut start: Option<usize> = None;
+        let mut prev: Option<char> = None;
+        for (i, c) in text.char_indices() {
+            if !c.is_alphanumeric() {
+                res.extend(start.take().map(|s| s..i));
+            } else if let (Some(s), Some(p)) = (start, prev) {
+                if self.split_on_numerics && p.is_numeric() != c.is_numeric() {
+                    res.push(s..i);
+                    start = Some(i);
+                }
+            } else {
+                start = Some(i);
+            }
+            prev = Some(c).filter(|c| c.is_alphanumeric());
+        }
+        res.extend(start.map(|s| s..text.len()));
+        res
+    }
+}
+
+impl TokenFilter for WordDelimiterFilter {
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let mut res = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            let parts = self.split(&token.text);
+            if parts.len() == 1 && parts[0].len() == token.text.len() {
+                res.push(token);
+                continue;
+            }
+            let catenated: String = parts.iter().map(|r| &token.text[r.clone()]).collect();
+            let mut subwords: Vec<Token> = parts.into_iter().map(|r| token.subword(r)).collect();
+            if self.catenate_all && subwords.len() > 1 && catenated != token.text {
+                subwords.push(Token {
+                    text: catenated,
+                    ..token.clone()
+                });
+            }
+            if self.preserve_original && !subwords.is_empty() {
+                res.push(token);
+            }
+            res.extend(subwords);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod compound_tests {
+    use crate::analyzer::Analyzer;
+    use crate::compound::{DictionaryCompoundFilter, WordDelimiterFilter};
+    use crate::filters::{LowercaseFilter, TokenFilter};
+    use crate::index::{Document, InvertedIndex};
+    use crate::tokenizer::{Token, Tokenize, Tokenizer, WhitespaceTokenizer};
+
+    fn texts(tokens: Vec<Token>) -> Vec<String> {
+        tokens.into_iter().map(|t| t.text).collect()
+    }
+
+    #[test]
+    fn test_decompose() {
+        let filter = DictionaryCompoundFilter::new(["donau", "dampf", "schiff", "Dampfschiff"]);
+        let res = texts(filter.filter(Tokenizer::new().tokens("Donaudampfschiff")));
+        let expected = [
+            "Donaudampfschiff",
+            "Donau",
+            "dampf",
+            "dampfschiff",
+            "schiff",
+        ];
+        assert_eq!(res, expected, "compound decomposition failed");
+    }
+
+    #[test]
+    fn test_decompose_longest_match() {
+        let mut filter = DictionaryCompoundFilter::new(["dampf", "dampfschiff", "schiff", "fahrt"]);
+        filter.only_longest_match = true;
+        let res = texts(filter.filter(Tokenizer::new().tokens("dampfschifffahrt kurz")));
+        let expected = ["dampfschifffahrt", "dampfschiff", "schiff", "fahrt", "kurz"];
+        assert_eq!(res, expected, "longest match decomposition failed");
+    }
+
+    #[test]
+    fn test_compound_search() {
+        let analyzer = Analyzer::custom(
+            Tokenizer::new(),
+            vec![
+                Box::new(LowercaseFilter),
+                Box::new(DictionaryCompoundFilter::new(["donau", "dampf", "schiff"])),
+            ],
+        );
+        let mut index = InvertedIndex::with_analyzer(analyzer);
+        index.add(&[
+            Document::new(1, "Das Donaudampfschiff fährt"),
+            Document::new(2, "Das Segelschiff fährt"),
+        ]);
+        assert_eq!(index.search("Dampfschiff"), [1], "compound search failed");
+    }
+
+    #[test]
+    fn test_word_delimiter() {
+        let filter = WordDelimiterFilter::new();
+        let tokens = filter.filter(WhitespaceTokenizer::new().tokens("wi-fi SD500 - plain"));
+        let res: Vec<(String, usize)> = tokens.into_iter().map(|t| (t.text, t.position)).collect();
+        let expected = [
+            ("wi-fi", 0),
+            ("wi", 0),
+            ("fi", 0),
+            ("wifi", 0),
+            ("SD500", 1),
+            ("SD", 1),
+            ("500", 1),
+            ("plain", 3),
+        ]
+        .map(|(t, p)| (t.to_string(), p));
+        assert_eq!(res, expected, "word delimiter failed");
+    }
+
+    #[test]
+    fn test_word_delimiter_offsets() {
+        let filter = WordDelimiterFilter {
+            split_on_numerics: false,
+            catenate_all: false,
+            preserve_original: false,
+        };
+        let text = "(e-mail) A4";
+        let res: Vec<&str> = filter
+            .filter(WhitespaceTokenizer::new().tokens(text))
+            .into_iter()
+            .map(|t| &text[t.offset])
+            .collect();
+        assert_eq!(res, ["e", "mail", "A4"], "word delimiter offsets failed");
+    }
+}
@@ -0,0 +1,247 @@
+/// Language detection picks the language of a text without any external service.
+/// The script of the text narrows down candidate languages, e.g. Cyrillic text is Russian,
+/// languages sharing a script are told apart by their stop words and distinctive letters.
+use crate::filters::Language;
+use std::collections::HashSet;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Writing systems of supported languages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Script {
+    Arabic,
+    Armenian,
+    Cyrillic,
+    Devanagari,
+    Greek,
+    Han,
+    Hangul,
+    Hebrew,
+    Kana,
+    Latin,
+    Tamil,
+}
+
+/// `LanguageDetector` detects the language of a text among candidate languages.
+pub struct LanguageDetector {
+    candidates: Vec<(Language, HashSet<String>)>,
+}
+
+/// `Default` chooses from all available languages.
+impl Default for LanguageDetector {
+    fn default() -> Self {
+        Self::new(Language::all().iter().copied())
+    }
+}
+
+impl LanguageDetector {
+    /// Creates a detector choosing from candidate languages.
+    /// Ties are resolved in favour of the language listed first.
+    pub fn new<I>(languages: I) -> Self
+    where
+        I: IntoIterator<Item = Language>,
+    {
+        LanguageDetector {
+            candidates: languages
+                .into_iter()
+                .map(|language| (language, language.get_stopwords()))
+                .collect(),
+        }
+    }
+
+    /// Returns candidate languages.
+    pub fn languages(&self) -> impl Iterator<Item = Language> + '_ {
+        self.candidates.iter().map(|(language, _)| *language)
+    }
+
+    /// Detects the language of a text.
+    /// Returns `None` if the text gives no evidence for any candidate language.
+    pub fn detect(&self, text: &str) -> Option<Language> {
+        let script = dominant_script(text)?;
+        let candidates: Vec<&(Language, HashSet<String>)> = self
+            .candidates
+            .iter()
+            .filter(|(language, _)| script_of(*language) == script)
+            .collect();
+        if let [(language, _)] = candidates[..] {
+            return Some(*language);
+        }
+
+        let words: Vec<String> = text.unicode_words().map(str::to_lowercase).collect();
+        let mut best = None;
+        let mut best_score = 0.0;
+        for (language, stop_words) in candidates {
+            let hits = words.iter().filter(|w| stop_words.contains(*w)).count();
+            let letters = distinctive_letters(*language);
+            let marks = text.chars().filter(|c| letters.contains(*c)).count();
+            let score = (hits as f64 + 0.5 * marks as f64) / words.len().max(1) as f64;
+            if score > best_score {
+                best = Some(*language);
+                best_score = score;
+            }
+        }
+        best
+    }
+}
+
+/// Returns the script most letters of a text are written in.
+/// Han ideographs count as kana in text with kana, Japanese mixes them.
+fn dominant_script(text: &str) -> Option<Script> {
+    let has_kana = text
+        .chars()
+        .any(|c| script_of_char(c) == Some(Script::Kana));
+    let mut counts: Vec<(Script, usize)> = Vec::new();
+    for script in text.chars().filter_map(script_of_char) {
+        let script = match script {
+            Script::Han if has_kana => Script::Kana,
+            script => script,
+        };
+        match counts.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((script, 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(_, count)| count)
+        .map(|(script, _)| script)
+}
+
+fn script_of_char(c: char) -> Option<Script> {
+    let script = match c {
+        '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => Script::Greek,
+        '\u{0400}'..='\u{04FF}' => Script::Cyrillic,
+        '\u{0530}'..='\u{058F}' => Script::Armenian,
+        '\u{0590}'..='\u{05FF}' | '\u{FB1D}'..='\u{FB4F}' => Script::Hebrew,
+        '\u{0600}'..='\u{06FF}' | '\u{0750}'..='\u{077F}' | '\u{FB50}'..='\u{FEFF}' => {
+            Script::Arabic
+        }
+        '\u{0900}'..='\u{097F}' => Script::Devanagari,
+        '\u{0B80}'..='\u{0BFF}' => Script::Tamil,
+        '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}' => {
+            Script::Hangul
+        }
+        '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' | '\u{FF66}'..='\u{FF9F}' => Script::Kana,
+        '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{2FA1F}' => Script::Han,
+        'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}' => Script::Latin,
+        _ => return None,
+    };
+    Some(script)
+}
+
+fn script_of(language: Language) -> Script {
+    match language {
+        Language::Arabic => Script::Arabic,
+        Language::Armenian => Script::Armenian,
+        Language::Chinese => Script::Han,
+        Language::Greek => Script::Greek,
+        Language::Japanese => Script::Kana,
+        Language::Korean => Script::Hangul,
+        Language::Nepali => Script::Devanagari,
+        Language::Russian => Script::Cyrillic,
+        Language::Tamil => Script::Tamil,
+        Language::Yiddish => Script::Hebrew,
+        _ => Script::Latin,
+    }
+}
+
+/// Letters which are common in a language and rare in other languages of the same script.
+fn distinctive_letters(language: Language) -> &'static str {
+    match language {
+        Language::Catalan => "òïŀ",
+        Language::Danish | Language::Norwegian => "æøå",
+        Language::Dutch => "ĳ",
+        Language::Finnish => "äö",
+        Language::French => "çéèêàœ",
+        Language::German => "äöüß",
+        Language::Hungarian => "őűáé",
+        Language::Italian => "àèìòù",
+        Language::Lithuanian => "ąčęėįšųūž",
+        Language::Portuguese => "ãõçá",
+        Language::Romanian => "ăâîșț",
+        Language::Spanish => "ñ¿¡áó",
+        Language::Swedish => "åäö",
+        Language::Turkish => "ğşıçİ",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod detect_tests {
+    use crate::detect::LanguageDetector;
+    use crate::filters::Language;
+
+    #[test]
+    fn test_detect_latin() {
+        let detector = LanguageDetector::default();
+        let texts = [
+            (
+                "The rain poured on the roof and it was cold",
+                Language::English,
+            ),
+            (
+                "Der Regen fiel auf das Dach und es war kalt",
+                Language::German,
+            ),
+            (
+                "La pluie tombait sur le toit et il faisait froid",
+                Language::French,
+            ),
+            (
+                "La lluvia caía sobre el tejado y hacía frío",
+                Language::Spanish,
+            ),
+        ];
+        for (text, language) in texts {
+            assert_eq!(
+                detector.detect(text),
+                Some(language),
+                "{text} detection failed"
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect_script() {
+        let detector = LanguageDetector::default();
+        let texts = [
+            ("Дождь лил на крышу", Language::Russian),
+            ("Η βροχή έπεφτε", Language::Greek),
+            ("我们在北京学习", Language::Chinese),
+            ("東京でラーメンを食べた", Language::Japanese),
+            ("서울에서 공부합니다", Language::Korean),
+            ("Երևանը մեծ քաղաք է", Language::Armenian),
+            ("मेरो घर काठमाडौंमा छ", Language::Nepali),
+            ("דער טאַטע איז אין שטוב", Language::Yiddish),
+        ];
+        for (text, language) in texts {
+            assert_eq!(
+                detector.detect(text),
+                Some(language),
+                "{text} detection failed"
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect_candidates() {
+        let detector = LanguageDetector::new([Language::English, Language::German]);
+        assert_eq!(
+            detector.detect("Дождь"),
+            None,
+            "unexpected language detected"
+        );
+        assert_eq!(
+            detector.detect("12345"),
+            None,
+            "unexpected language detected"
+        );
+        assert_eq!(
+            detector.detect("Straße"),
+            Some(Language::German),
+            "distinctive letters failed"
+        );
+    }
+}
@@ -0,0 +1,265 @@
+/// Score explanations show how the score of a document for a query is computed,
+/// e.g. to debug ranking.
+/// An explanation is a tree, every node has a value, a description of how the value is
+/// computed, and the explanations of the values it is computed from.
+use crate::index::{idf, tf_norm, InvertedIndex, B, K1};
+use crate::query::Query;
+use std::fmt;
+
+/// `Explanation` is a node of the score explanation tree.
+/// `value`: is a score or a value it's computed from
+/// `description`: tells what the value is and how it's computed from `details`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Explanation {
+    pub value: f64,
+    pub description: String,
+    pub details: Vec<Explanation>,
+}
+
+impl Explanation {
+    fn new(value: f64, description: impl Into<String>, details: Vec<Explanation>) -> Self {
+        Explanation {
+            value,
+            description: description.into(),
+            details,
+        }
+    }
+
+    fn leaf(value: f64, description: impl Into<String>) -> Self {
+        Self::new(value, description, Vec::new())
+    }
+}
+
+impl InvertedIndex {
+    /// Explains the score of a document for a query of `search_scored`.
+    /// The value of the root is the score, 0 for a document which doesn't match.
+    pub fn explain(&self, text: &str, id: u64) -> Explanation {
+        self.explain_query(&Query::parse(text), id)
+    }
+
+    /// Explains the score of a document for a query of `search_query`, see `explain`.
+    pub fn explain_query(&self, query: &Query, id: u64) -> Explanation {
+        let index = self.language_index(self.language(id));
+        let Some(length) = index.doc_length(id) else {
+            return Explanation::leaf(0.0, format!("no match, document {id} is not indexed"));
+        };
+        let explanation = index.explain_terms(query, id, length);
+        if !self.has_language_detection() || explanation.value == 0.0 {
+            return explanation;
+        }
+        let language = self
+            .language(id)
+            .map_or("default".to_string(), |l| l.to_string());
+        let max = index
+            .scored(query, false)
+            .into_iter()
+            .map(|(_, score)| score)
+            .fold(0.0, f64::max);
+        Explanation::new(
+            explanation.value / max,
+            "normalized score, score / max score of the language",
+            vec![
+                explanation,
+                Explanation::leaf(max, format!("max score of {language} documents")),
+            ],
+        )
+    }
+
+    /// Explains the sum of BM25 scores of terms of this index only.
+    fn explain_terms(&self, query: &Query, id: u64, length: usize) -> Explanation {
+        let num_docs = self.num_docs();
+        let avg_length = self.avg_length();
+        let mut details = Vec::new();
+        let mut missing = Vec::new();
+        for (term, boost) in query.terms(self.analyzer()) {
+            let Some(ids) = self.postings(&term) else {
+                details.push(Explanation::leaf(
+                    0.0,
+                    format!("term \"{term}\" is not indexed, it's ignored"),
+                ));
+                continue;
+            };
+            if !ids.contains(&id) {
+                missing.push(term);
+                continue;
+            }
+            let doc_freq = ids.len();
+            let idf = Explanation::new(
+                idf(doc_freq, num_docs),
+                "idf, computed as ln(1 + (N - n + 0.5) / (n + 0.5)) from:",
+                vec![
+                    Explanation::leaf(doc_freq as f64, "n, number of documents with the term"),
+                    Explanation::leaf(num_docs as f64, "N, total number of documents"),
+                ],
+            );
+            let fields: Vec<Explanation> = self
+                .term_fields(id, &term)
+                .map(|field| {
+                    Explanation::leaf(query.field_boost(field), format!("boost of {field}"))
+                })
+                .collect();
+            let tf = fields.iter().map(|f| f.value).sum();
+            let norm = Explanation::new(
+                tf_norm(tf, length as f64, avg_length),
+                "tf norm, computed as (k1 + 1) * tf / (tf + k1 * (1 - b + b * dl / avgdl)) from:",
+                vec![
+                    Explanation::new(
+                        tf,
+                        "tf, term frequency, sum of boosts of fields with the term, \
+                         every term counts once per field:",
+                        fields,
+                    ),
+                    Explanation::leaf(K1, "k1, term frequency saturation"),
+                    Explanation::leaf(B, "b, length normalization"),
+                    Explanation::leaf(length as f64, "dl, number of terms of the document"),
+                    Explanation::leaf(avg_length, "avgdl, average number of terms"),
+                ],
+            );
+            details.push(Explanation::new(
+                boost * idf.value * norm.value,
+                format!("score of \"{term}\", product of:"),
+                vec![Explanation::leaf(boost, "boost"), idf, norm],
+            ));
+        }
+        if !missing.is_empty() {
+            let description = format!(
+                "no match, document {id} doesn't contain terms: {}",
+                missing.join(", ")
+            );
+            return Explanation::new(0.0, description, details);
+        }
+        let score = details.iter().map(|d| d.value).sum();
+        Explanation::new(score, "sum of term scores:", details)
+    }
+}
+
+/// Displays the tree with a node per line, details are indented, e.g.
+/// "0.52 = sum of term scores:".
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let indent = f.width().unwrap_or_default();
+        writeln!(f, "{:indent$}{} = {}", "", self.value, self.description)?;
+        for detail in &self.details {
+            write!(f, "{detail:width$}", width = indent + 2)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod explain_tests {
+    use crate::detect::LanguageDetector;
+    use crate::filters::Language;
+    use crate::index::{Document, InvertedIndex};
+    use crate::query::Query;
+    use crate::test_support::docs;
+
+    #[test]
+    fn test_explain_score() {
+        let mut idx = InvertedIndex::default();
+        idx.add(&docs(&[
+            "Rust language with a long list of features",
+            "Rust language",
+            "Python language",
+        ]));
+        for (id, score) in idx.search_scored("rust^2 language") {
+            let explanation = idx.explain("rust^2 language", id);
+            assert!(
+                (explanation.value - score).abs() < 1e-12,
+                "explained score differs"
+            );
+        }
+
+        let explanation = idx.explain("rust^2 language", 2);
+        let rust = &explanation.details[0];
+        assert_eq!(rust.details[0].value, 2.0, "boost is not explained");
+        let idf = &rust.details[1];
+        let values: Vec<f64> = idf.details.iter().map(|d| d.value).collect();
+        assert_eq!(values, [2.0, 3.0], "idf is not explained");
+        let text = explanation.to_string();
+        assert!(
+            text.lines().nth(1).unwrap().starts_with("  "),
+            "details are not indented"
+        );
+    }
+
+    #[test]
+    fn test_explain_no_match() {
+        let mut idx = InvertedIndex::default();
+        idx.add(&docs(&["Rust language", "Python language"]));
+        let explanation = idx.explain("python language cobol", 1);
+        assert_eq!(explanation.value, 0.0, "missing term matched");
+        assert!(
+            explanation.description.contains("python"),
+            "missing term is not explained"
+        );
+        assert_eq!(
+            idx.explain("rust", 7).value,
+            0.0,
+            "unknown document matched"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_explain_json() {
+        let mut idx = InvertedIndex::default();
+        idx.add(&docs(&["Rust language", "Python language"]));
+        let json = serde_json::to_value(idx.explain("rust cobol", 1)).unwrap();
+        assert_eq!(
+            json["details"][0]["details"][0]["value"], 1.0,
+            "serializing failed"
+        );
+        assert_eq!(json["details"][1]["value"], 0.0, "serializing failed");
+    }
+
+    #[test]
+    fn test_explain_normalized() {
+        let detector = LanguageDetector::new([Language::English, Language::German]);
+        let mut idx = InvertedIndex::with_language_detection(detector, Language::English);
+        idx.add(&docs(&[
+            "Rust is a fast language",
+            "Rust is a fast and safe language for the systems of the future",
+            "Rust ist eine schnelle Sprache",
+        ]));
+        for (id, score) in idx.search_scored("rust") {
+            let explanation = idx.explain("rust", id);
+            assert!(
+                (explanation.value - score).abs() < 1e-12,
+                "explained normalized score differs"
+            );
+        }
+    }
+
+    #[test]
+    fn test_explain_field_boosts() {
+        let mut idx = InvertedIndex::default();
+        idx.add(&[
+            Document::new(1, "Rust is a fast language").with_field("title", "Rust"),
+            Document::new(2, "Rust and Python language").with_field("title", "Python"),
+        ]);
+        let query = Query::parse("rust language").with_field_boosts(&["title^2"]);
+        for (id, score) in idx.search_query(&query) {
+            let explanation = idx.explain_query(&query, id);
+            assert!(
+                (explanation.value - score).abs() < 1e-12,
+                "explained field score differs"
+            );
+        }
+
+        let explanation = idx.explain_query(&query, 1);
+        let tf = &explanation.details[0].details[2].details[0];
+        assert_eq!(tf.value, 3.0, "tf is not explained");
+        let boosts: Vec<(f64, &str)> = tf
+            .details
+            .iter()
+            .map(|d| (d.value, d.description.as_str()))
+            .collect();
+        assert_eq!(
+            boosts,
+            [(1.0, "boost of text"), (2.0, "boost of title")],
+            "field boosts are not explained"
+        );
+    }
+}
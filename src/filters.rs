@@ -1,16 +1,54 @@
 /// `Filters` include stop words, lowercase, and stemming filters.
+use crate::light_stemmers;
+use crate::tokenizer::Token;
 use rust_stemmers::{Algorithm, Stemmer};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::borrow::Cow;
 use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 
 pub struct Filters {
+    stop_words: StopWordsFilter,
+    stemming: StemmingFilter,
+}
+
+/// `TokenFilter` is implemented by all filters, so they can be chained in an `Analyzer`.
+pub trait TokenFilter: Send + Sync {
+    /// Transforms tokens, a filter may change, remove or add tokens.
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token>;
+}
+
+/// `LowercaseFilter` makes all tokens lowercase.
+pub struct LowercaseFilter;
+
+/// `StopWordsFilter` removes stop words of a language.
+/// Positions of removed tokens are left empty.
+pub struct StopWordsFilter {
     stop_words_list: HashSet<String>,
-    stemmer: Stemmer,
+}
+
+/// `StemmingFilter` reduces tokens to their stems.
+/// Tokens are kept as is for languages without a stemming algorithm.
+pub struct StemmingFilter {
+    stemmer: Option<Stemmer>,
+    native: Option<fn(&str) -> String>,
 }
 
 /// Available languages for stemming.
+/// Armenian, Basque, Catalan, Indonesian, Irish, Lithuanian, Nepali and Yiddish use light
+/// stemmers of this crate, which remove fewer suffixes than their Snowball algorithms,
+/// other languages with stemming use the Snowball stemmers of `rust_stemmers`.
+/// A language is parsed from its ISO 639-1 code or English name, e.g. "de" or "German",
+/// and serialized as its name, so it can be selected in configuration files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Language {
     Arabic,
+    Armenian,
+    Basque,
+    Catalan,
+    Chinese,
     Danish,
     Dutch,
     English,
@@ -19,7 +57,13 @@ pub enum Language {
     German,
     Greek,
     Hungarian,
+    Indonesian,
+    Irish,
     Italian,
+    Japanese,
+    Korean,
+    Lithuanian,
+    Nepali,
     Norwegian,
     Portuguese,
     Romanian,
@@ -28,13 +72,90 @@ pub enum Language {
     Swedish,
     Tamil,
     Turkish,
+    Yiddish,
 }
 
+/// All available languages.
+const LANGUAGES: [Language; 29] = [
+    Language::Arabic,
+    Language::Armenian,
+    Language::Basque,
+    Language::Catalan,
+    Language::Chinese,
+    Language::Danish,
+    Language::Dutch,
+    Language::English,
+    Language::Finnish,
+    Language::French,
+    Language::German,
+    Language::Greek,
+    Language::Hungarian,
+    Language::Indonesian,
+    Language::Irish,
+    Language::Italian,
+    Language::Japanese,
+    Language::Korean,
+    Language::Lithuanian,
+    Language::Nepali,
+    Language::Norwegian,
+    Language::Portuguese,
+    Language::Romanian,
+    Language::Russian,
+    Language::Spanish,
+    Language::Swedish,
+    Language::Tamil,
+    Language::Turkish,
+    Language::Yiddish,
+];
+
 impl Language {
-    /// Get algorithm matching variant of language
-    fn get(&self) -> Algorithm {
+    /// Returns all available languages.
+    pub fn all() -> &'static [Language] {
+        &LANGUAGES
+    }
+
+    /// Returns the ISO 639-1 code of a language.
+    pub fn code(&self) -> &'static str {
         use self::Language::*;
         match self {
+            Arabic => "ar",
+            Armenian => "hy",
+            Basque => "eu",
+            Catalan => "ca",
+            Chinese => "zh",
+            Danish => "da",
+            Dutch => "nl",
+            English => "en",
+            Finnish => "fi",
+            French => "fr",
+            German => "de",
+            Greek => "el",
+            Hungarian => "hu",
+            Indonesian => "id",
+            Irish => "ga",
+            Italian => "it",
+            Japanese => "ja",
+            Korean => "ko",
+            Lithuanian => "lt",
+            Nepali => "ne",
+            Norwegian => "no",
+            Portuguese => "pt",
+            Romanian => "ro",
+            Russian => "ru",
+            Spanish => "es",
+            Swedish => "sv",
+            Tamil => "ta",
+            Turkish => "tr",
+            Yiddish => "yi",
+        }
+    }
+
+    /// Get the `rust_stemmers` algorithm matching variant of language.
+    /// Returns `None` for Chinese, Japanese and Korean, which have no stemming algorithm,
+    /// and for languages missing from `rust_stemmers`, which have a light one.
+    pub fn get(&self) -> Option<Algorithm> {
+        use self::Language::*;
+        let algorithm = match self {
             Arabic => Algorithm::Arabic,
             Danish => Algorithm::Danish,
             Dutch => Algorithm::Dutch,
@@ -53,15 +174,47 @@ impl Language {
             Swedish => Algorithm::Swedish,
             Tamil => Algorithm::Tamil,
             Turkish => Algorithm::Turkish,
-        }
+            Armenian | Basque | Catalan | Chinese | Indonesian | Irish | Japanese | Korean
+            | Lithuanian | Nepali | Yiddish => return None,
+        };
+        Some(algorithm)
+    }
+
+    /// Get light stemmer of a language missing from `rust_stemmers`.
+    fn native(&self) -> Option<fn(&str) -> String> {
+        use self::Language::*;
+        let stemmer = match self {
+            Armenian => light_stemmers::armenian,
+            Basque => light_stemmers::basque,
+            Catalan => light_stemmers::catalan,
+            Indonesian => light_stemmers::indonesian,
+            Irish => light_stemmers::irish,
+            Lithuanian => light_stemmers::lithuanian,
+            Nepali => light_stemmers::nepali,
+            Yiddish => light_stemmers::yiddish,
+            _ => return None,
+        };
+        Some(stemmer)
+    }
+
+    /// Returns true for languages written without spaces between words.
+    pub(crate) fn is_cjk(&self) -> bool {
+        matches!(
+            self,
+            Language::Chinese | Language::Japanese | Language::Korean
+        )
     }
 
     /// Get stopwords for a given language.
-    fn get_stopwords(&self) -> HashSet<String> {
+    pub(crate) fn get_stopwords(&self) -> HashSet<String> {
         use self::Language::*;
         use crate::stopwords;
         let stop_words = match self {
             Arabic => stopwords::ARABIC,
+            Armenian => stopwords::ARMENIAN,
+            Basque => stopwords::BASQUE,
+            Catalan => stopwords::CATALAN,
+            Chinese => stopwords::CHINESE,
             Danish => stopwords::DANISH,
             Dutch => stopwords::DUTCH,
             English => stopwords::ENGLISH,
@@ -70,7 +223,13 @@ impl Language {
             German => stopwords::GERMAN,
             Greek => stopwords::GREEK,
             Hungarian => stopwords::HUNGARIAN,
+            Indonesian => stopwords::INDONESIAN,
+            Irish => stopwords::IRISH,
             Italian => stopwords::ITALIAN,
+            Japanese => stopwords::JAPANESE,
+            Korean => stopwords::KOREAN,
+            Lithuanian => stopwords::LITHUANIAN,
+            Nepali => stopwords::NEPALI,
             Norwegian => stopwords::NORWEGIAN,
             Portuguese => stopwords::PORTUGUESE,
             Romanian => stopwords::ROMANIAN,
@@ -79,6 +238,7 @@ impl Language {
             Swedish => stopwords::SWEDISH,
             Tamil => stopwords::TAMIL,
             Turkish => stopwords::TURKISH,
+            Yiddish => stopwords::YIDDISH,
         };
         stop_words
             .iter()
@@ -87,6 +247,64 @@ impl Language {
     }
 }
 
+/// `ParseLanguageError` is returned for unknown language codes and names.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseLanguageError {
+    input: String,
+}
+
+impl fmt::Display for ParseLanguageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown language \"{}\"", self.input)
+    }
+}
+
+impl std::error::Error for ParseLanguageError {}
+
+/// Parses ISO 639-1 codes and English names, case-insensitively.
+/// Norwegian Bokmål "nb" and Nynorsk "nn" codes are parsed as Norwegian.
+impl FromStr for Language {
+    type Err = ParseLanguageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim().to_lowercase();
+        if matches!(input.as_str(), "nb" | "nn") {
+            return Ok(Language::Norwegian);
+        }
+        LANGUAGES
+            .into_iter()
+            .find(|language| {
+                language.code() == input || language.to_string().to_lowercase() == input
+            })
+            .ok_or(ParseLanguageError {
+                input: s.to_string(),
+            })
+    }
+}
+
+/// Displays the English name of a language.
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// `Language` is serialized by name and deserialized from a name or an ISO 639-1 code.
+#[cfg(feature = "serde")]
+impl Serialize for Language {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Language {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <Cow<str>>::deserialize(deserializer)?;
+        name.parse().map_err(de::Error::custom)
+    }
+}
+
 /// `Default` assume that a text will be in English.
 impl Default for Filters {
     fn default() -> Self {
@@ -98,8 +316,8 @@ impl Filters {
     /// Creates a `Filter` instance with custom language.
     pub fn new(language: Language) -> Self {
         Filters {
-            stop_words_list: language.get_stopwords(),
-            stemmer: Stemmer::create(language.get()),
+            stop_words: StopWordsFilter::new(&language),
+            stemming: StemmingFilter::new(&language),
         }
     }
 
@@ -118,7 +336,7 @@ impl Filters {
     {
         let set_of_tokens: HashSet<String> = tokens.into_iter().collect();
         set_of_tokens
-            .difference(&self.stop_words_list)
+            .difference(&self.stop_words.stop_words_list)
             .cloned()
             .collect::<Vec<String>>()
             .into_iter()
@@ -129,17 +347,79 @@ impl Filters {
     where
         I: Iterator<Item = String> + 'a,
     {
-        tokens.map(|t| match self.stemmer.stem(&t) {
+        tokens.map(|t| self.stemming.stem(&t))
+    }
+}
+
+impl TokenFilter for LowercaseFilter {
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+        tokens
+            .into_iter()
+            .map(|t| Token {
+                text: t.text.to_lowercase(),
+                ..t
+            })
+            .collect()
+    }
+}
+
+impl StopWordsFilter {
+    /// Creates a filter with stop words of a language.
+    pub fn new(language: &Language) -> Self {
+        StopWordsFilter {
+            stop_words_list: language.get_stopwords(),
+        }
+    }
+}
+
+impl TokenFilter for StopWordsFilter {
+    fn filter(&self, mut tokens: Vec<Token>) -> Vec<Token> {
+        tokens.retain(|t| !self.stop_words_list.contains(&t.text));
+        tokens
+    }
+}
+
+impl StemmingFilter {
+    /// Creates a filter with the stemming algorithm of a language.
+    pub fn new(language: &Language) -> Self {
+        StemmingFilter {
+            stemmer: language.get().map(Stemmer::create),
+            native: language.native(),
+        }
+    }
+
+    pub(crate) fn stem(&self, token: &str) -> String {
+        if let Some(native) = self.native {
+            return native(token);
+        }
+        let Some(stemmer) = &self.stemmer else {
+            return token.to_string();
+        };
+        match stemmer.stem(token) {
             Cow::Owned(stemmed_str) => stemmed_str,
             Cow::Borrowed(stemmed_str) => stemmed_str.to_string(),
-        })
+        }
+    }
+}
+
+impl TokenFilter for StemmingFilter {
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+        tokens
+            .into_iter()
+            .map(|t| Token {
+                text: self.stem(&t.text),
+                ..t
+            })
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod filters_tests {
-    use crate::filters::Filters;
-    use crate::filters::Language::Russian;
+    use crate::filters::Language::{self, Russian};
+    use crate::filters::{Filters, StopWordsFilter, TokenFilter};
+    use crate::tokenizer::{Tokenize, Tokenizer};
+    use rust_stemmers::Algorithm;
 
     #[test]
     fn test_lowercase() {
@@ -198,4 +478,112 @@ mod filters_tests {
         let expected = ["работа", "работа", "работа", "работа"];
         assert_eq!(res, expected, "stemming custom lang failed");
     }
+
+    #[test]
+    fn test_stemming_light() {
+        let languages = [
+            (Language::Armenian, "քաղաքներից"),
+            (Language::Basque, "mendiko"),
+            (Language::Catalan, "cantaven"),
+            (Language::Indonesian, "dimakan"),
+            (Language::Irish, "bhád"),
+            (Language::Lithuanian, "namuose"),
+            (Language::Nepali, "घरहरूमा"),
+            (Language::Yiddish, "גערעדט"),
+        ];
+        let res: Vec<String> = languages
+            .into_iter()
+            .flat_map(|(language, word)| {
+                let filter = Filters::new(language);
+                let res: Vec<String> = filter.stemming([word.to_string()].into_iter()).collect();
+                res
+            })
+            .collect();
+        let expected = ["քաղաք", "mendi", "cant", "makan", "bád", "nam", "घर", "רעד"];
+        assert_eq!(res, expected, "stemming light stemmers failed");
+    }
+
+    #[test]
+    fn test_language_algorithm() {
+        let res = [Language::German, Language::Nepali, Language::Chinese].map(|l| l.get());
+        let expected = [Some(Algorithm::German), None, None];
+        assert_eq!(res, expected, "language algorithm failed");
+    }
+
+    #[test]
+    fn test_stop_words_new_languages() {
+        let texts = [
+            (Language::Armenian, "և"),
+            (Language::Basque, "eta"),
+            (Language::Catalan, "amb"),
+            (Language::Indonesian, "yang"),
+            (Language::Irish, "agus"),
+            (Language::Lithuanian, "ir"),
+            (Language::Nepali, "पनि"),
+            (Language::Yiddish, "און"),
+        ];
+        for (language, stop_word) in texts {
+            let filter = Filters::new(language);
+            let tokens = [stop_word, "word"].into_iter().map(str::to_string);
+            let res: Vec<String> = filter.stop_words(tokens).collect();
+            assert_eq!(res, ["word"], "stop words for {language:?} failed");
+        }
+    }
+
+    #[test]
+    fn test_parse_language() {
+        let res: Vec<Language> = ["de", "German", "ENGLISH", " hy ", "nb"]
+            .into_iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        let expected = [
+            Language::German,
+            Language::German,
+            Language::English,
+            Language::Armenian,
+            Language::Norwegian,
+        ];
+        assert_eq!(res, expected, "parsing language failed");
+        assert!(
+            "klingon".parse::<Language>().is_err(),
+            "unknown language parsed"
+        );
+        for language in Language::all() {
+            let parsed: Language = language.code().parse().unwrap();
+            assert_eq!(parsed, *language, "parsing {language} code failed");
+            let parsed: Language = language.to_string().parse().unwrap();
+            assert_eq!(parsed, *language, "parsing {language} name failed");
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_language() {
+        let json = serde_json::to_string(&[Language::English, Language::Yiddish]).unwrap();
+        assert_eq!(
+            json, r#"["English","Yiddish"]"#,
+            "serializing language failed"
+        );
+        let res: Vec<Language> = serde_json::from_str(r#"["ru", "catalan"]"#).unwrap();
+        let expected = [Language::Russian, Language::Catalan];
+        assert_eq!(res, expected, "deserializing language failed");
+        assert!(
+            serde_json::from_str::<Language>(r#""xx""#).is_err(),
+            "unknown language deserialized"
+        );
+    }
+
+    #[test]
+    fn test_stop_words_filter_keeps_positions() {
+        let filter = StopWordsFilter::new(&Russian);
+        let tokens = Tokenizer::new().tokens("я бы тут остался");
+
+        let res: Vec<(String, usize)> = filter
+            .filter(tokens)
+            .into_iter()
+            .map(|t| (t.text, t.position))
+            .collect();
+        let expected = [("остался".to_string(), 3)];
+        assert_eq!(res, expected, "stop words filter lost positions");
+    }
 }
@@ -0,0 +1,662 @@
+/// Hunspell dictionaries describe words of a language with a list of stems (`.dic` file)
+/// and rules to build word forms from them (`.aff` file).
+/// They are available for most languages, so `HunspellDictionary` broadens stemming and
+/// spell checking beyond languages with a Snowball algorithm.
+/// Prefixes, suffixes, their cross products and two suffixes in a row are supported,
+/// compounding rules are not.
+/// For more details, see https://man.archlinux.org/man/hunspell.5
+use crate::code_pages::code_page;
+use crate::filters::TokenFilter;
+use crate::tokenizer::Token;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Maximal number of spelling suggestions.
+const MAX_SUGGESTIONS: usize = 10;
+
+/// `HunspellDictionary` stems and checks spelling of words.
+#[derive(Debug, Default)]
+pub struct HunspellDictionary {
+    words: HashMap<String, Vec<Vec<Flag>>>,
+    prefixes: HashMap<String, Vec<Affix>>,
+    suffixes: HashMap<String, Vec<Affix>>,
+    need_affix: Option<Flag>,
+    forbidden: Option<Flag>,
+    only_in_compound: Option<Flag>,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+}
+
+/// `HunspellStemFilter` replaces tokens with their stems at the same position.
+/// Tokens with several stems produce several tokens, unknown tokens are kept as is.
+/// Lowercase tokens are also looked up capitalized, so proper nouns are stemmed
+/// after lowercasing.
+pub struct HunspellStemFilter {
+    dictionary: Arc<HunspellDictionary>,
+}
+
+type Flag = u32;
+
+/// Format of flags in the `.aff` and `.dic` files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FlagFormat {
+    Char,
+    Long,
+    Num,
+}
+
+/// A prefix or suffix rule.
+/// `strip`: is removed from a stem before `add` is added
+/// `condition`: must match the start of a stem for prefixes and the end for suffixes
+/// `continuation`: are flags of affixes which may be added after this one
+#[derive(Clone, Debug)]
+struct Affix {
+    flag: Flag,
+    cross_product: bool,
+    strip: String,
+    condition: Vec<Pattern>,
+    continuation: Vec<Flag>,
+}
+
+/// A part of an affix condition, which matches a single character.
+#[derive(Clone, Debug)]
+enum Pattern {
+    Any,
+    Char(char),
+    Class { negated: bool, chars: Vec<char> },
+}
+
+impl HunspellDictionary {
+    /// Loads a dictionary from `.aff` and `.dic` files.
+    pub fn load<A: AsRef<Path>, D: AsRef<Path>>(aff: A, dic: D) -> io::Result<Self> {
+        Self::from_readers(fs::File::open(aff)?, fs::File::open(dic)?)
+    }
+
+    /// Reads a dictionary from `.aff` and `.dic` contents.
+    /// UTF-8, ISO8859-1 and single-byte code pages like ISO8859-2, KOI8-R and
+    /// microsoft-cp1251 are supported, see `code_pages`.
+    pub fn from_readers<A: Read, D: Read>(mut aff: A, mut dic: D) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        aff.read_to_end(&mut bytes)?;
+        let encoding = String::from_utf8_lossy(&bytes)
+            .lines()
+            .find_map(|line| line.strip_prefix("SET ").map(|e| e.trim().to_uppercase()))
+            .unwrap_or_else(|| "UTF-8".to_string());
+        let aff = decode(bytes, &encoding)?;
+        let mut bytes = Vec::new();
+        dic.read_to_end(&mut bytes)?;
+        let dic = decode(bytes, &encoding)?;
+
+        let mut dictionary = HunspellDictionary::default();
+        let aliases = dictionary.parse_aff(&aff)?;
+        let format = flag_format(&aff);
+        for line in dic.lines().skip(1) {
+            let entry = line.split_whitespace().next().unwrap_or_default();
+            if entry.is_empty() {
+                continue;
+            }
+            let (word, flags) = match entry.split_once('/') {
+                Some((word, flags)) => (word, resolve_flags(flags, format, &aliases)?),
+                None => (entry, Vec::new()),
+            };
+            dictionary
+                .words
+                .entry(word.to_string())
+                .or_default()
+                .push(flags);
+        }
+        Ok(dictionary)
+    }
+
+    /// Returns stems of a word, a correctly spelled word may be a stem itself.
+    pub fn stems(&self, word: &str) -> Vec<String> {
+        let mut res = Vec::new();
+        for variant in case_variants(word) {
+            if self.is_forbidden(&variant) {
+                continue;
+            }
+            if self.is_standalone(&variant) {
+                res.push(variant.clone());
+            }
+            self.affix_stems(&variant, &mut res);
+        }
+        let mut seen = Vec::new();
+        res.retain(|stem| {
+            let new = !seen.contains(stem);
+            seen.push(stem.clone());
+            new
+        });
+        res
+    }
+
+    /// Returns true if a word is spelled correctly.
+    /// Capitalized and uppercase words are also checked in lowercase, so words at
+    /// the beginning of a sentence are correct.
+    pub fn check(&self, word: &str) -> bool {
+        !word.is_empty() && !self.stems(word).is_empty()
+    }
+
+    /// Suggests corrections of a misspelled word.
+    /// Replacements from the `REP` table go first, then words one edit away,
+    /// edits insert and replace characters from the `TRY` list.
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let mut candidates = Vec::new();
+        for (from, to) in &self.replacements {
+            for (i, _) in word.match_indices(from.as_str()) {
+                candidates.push(format!("{}{to}{}", &word[..i], &word[i + from.len()..]));
+            }
+        }
+        for i in 0..chars.len() {
+            if i + 1 < chars.len() {
+                let mut swapped = chars.clone();
+                swapped.swap(i, i + 1);
+                candidates.push(swapped.into_iter().collect());
+            }
+            for &c in &self.try_chars {
+                let mut replaced = chars.clone();
+                replaced[i] = c;
+                candidates.push(replaced.into_iter().collect());
+            }
+            let mut deleted = chars.clone();
+            deleted.remove(i);
+            candidates.push(deleted.into_iter().collect());
+        }
+        for i in 0..=chars.len() {
+            for &c in &self.try_chars {
+                let mut inserted = chars.clone();
+                inserted.insert(i, c);
+                candidates.push(inserted.into_iter().collect());
+            }
+        }
+
+        let mut res: Vec<String> = Vec::new();
+        for candidate in candidates {
+            if candidate != word && !res.contains(&candidate) && self.check(&candidate) {
+                res.push(candidate);
+            }
+            if res.len() == MAX_SUGGESTIONS {
+                break;
+            }
+        }
+        res
+    }
+
+    /// Parses affix rules and options, returns flag aliases.
+    fn parse_aff(&mut self, aff: &str) -> io::Result<Vec<Vec<Flag>>> {
+        let format = flag_format(aff);
+        let mut aliases = Vec::new();
+        let mut alias_count = None;
+        // Flag, cross product and remaining entries of the current affix rule group.
+        let mut group: Option<(Flag, bool, usize)> = None;
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let Some(&kind) = fields.first() else {
+                continue;
+            };
+            match (kind, &fields[1..]) {
+                ("PFX" | "SFX", [flag, cross, count, ..])
+                    if group.is_none_or(|(_, _, remaining)| remaining == 0) =>
+                {
+                    let flag = parse_flag(flag, format)?;
+                    let count = count.parse().map_err(|_| invalid(line))?;
+                    group = Some((flag, *cross == "Y", count));
+                }
+                ("PFX" | "SFX", [_, strip, add, rest @ ..]) => {
+                    let Some((flag, cross_product, remaining)) = group.as_mut() else {
+                        return Err(invalid(line));
+                    };
+                    *remaining = remaining.saturating_sub(1);
+                    let (add, continuation) = match add.split_once('/') {
+                        Some((add, flags)) => (add, resolve_flags(flags, format, &aliases)?),
+                        None => (*add, Vec::new()),
+                    };
+                    let affix = Affix {
+                        flag: *flag,
+                        cross_product: *cross_product,
+                        strip: empty_if_zero(strip).to_string(),
+                        condition: parse_condition(rest.first().copied().unwrap_or(".")),
+                        continuation,
+                    };
+                    let affixes = if kind == "PFX" {
+                        &mut self.prefixes
+                    } else {
+                        &mut self.suffixes
+                    };
+                    affixes
+                        .entry(empty_if_zero(add).to_string())
+                        .or_default()
+                        .push(affix);
+                }
+                ("AF", [count]) if alias_count.is_none() => {
+                    alias_count = Some(count.parse::<usize>().map_err(|_| invalid(line))?);
+                }
+                ("AF", [flags, ..]) => aliases.push(parse_flags(flags, format)?),
+                ("NEEDAFFIX", [flag, ..]) => self.need_affix = Some(parse_flag(flag, format)?),
+                ("FORBIDDENWORD", [flag, ..]) => self.forbidden = Some(parse_flag(flag, format)?),
+                ("ONLYINCOMPOUND", [flag, ..]) => {
+                    self.only_in_compound = Some(parse_flag(flag, format)?)
+                }
+                ("TRY", [chars, ..]) => self.try_chars = chars.chars().collect(),
+                ("REP", [from, to, ..]) => {
+                    self.replacements
+                        .push((from.replace('_', " "), to.replace('_', " ")));
+                }
+                _ => {}
+            }
+        }
+        Ok(aliases)
+    }
+
+    /// Collects stems of a word with affixes removed.
+    fn affix_stems(&self, word: &str, res: &mut Vec<String>) {
+        self.suffix_stems(word, None, None, res);
+        // A prefix may add nothing, so the whole word may follow it.
+        for end in word.char_indices().map(|(i, _)| i) {
+            let Some(prefixes) = self.prefixes.get(&word[..end]) else {
+                continue;
+            };
+            for prefix in prefixes {
+                let stem = format!("{}{}", prefix.strip, &word[end..]);
+                if !matches_start(&prefix.condition, &stem) {
+                    continue;
+                }
+                if self.has_flag(&stem, prefix.flag) {
+                    res.push(stem.clone());
+                }
+                if prefix.cross_product {
+                    self.suffix_stems(&stem, None, Some(prefix.flag), res);
+                }
+            }
+        }
+    }
+
+    /// Collects stems of a word with one or two suffixes removed.
+    /// `outer`: is a flag of a removed suffix which must continue the next one
+    /// `prefix`: is a flag of a removed prefix which the stem must also have
+    fn suffix_stems(
+        &self,
+        word: &str,
+        outer: Option<Flag>,
+        prefix: Option<Flag>,
+        res: &mut Vec<String>,
+    ) {
+        // A suffix may add nothing, so the whole word may precede it.
+        let starts = word.char_indices().skip(1).map(|(i, _)| i);
+        for start in starts.chain([word.len()]) {
+            let Some(suffixes) = self.suffixes.get(&word[start..]) else {
+                continue;
+            };
+            for suffix in suffixes {
+                if outer.is_some_and(|flag| !suffix.continuation.contains(&flag))
+                    || (prefix.is_some() && !suffix.cross_product)
+                {
+                    continue;
+                }
+                let stem = format!("{}{}", &word[..start], suffix.strip);
+                if !matches_end(&suffix.condition, &stem) {
+                    continue;
+                }
+                let prefixed = prefix.is_none_or(|flag| self.has_flag(&stem, flag));
+                if prefixed && self.has_flag(&stem, suffix.flag) {
+                    res.push(stem.clone());
+                }
+                if outer.is_none() {
+                    self.suffix_stems(&stem, Some(suffix.flag), prefix, res);
+                }
+            }
+        }
+    }
+
+    fn has_flag(&self, word: &str, flag: Flag) -> bool {
+        self.words
+            .get(word)
+            .is_some_and(|homonyms| homonyms.iter().any(|flags| flags.contains(&flag)))
+    }
+
+    /// Returns true if a word is valid without affixes.
+    fn is_standalone(&self, word: &str) -> bool {
+        let restricted = [self.need_affix, self.only_in_compound];
+        self.words.get(word).is_some_and(|homonyms| {
+            homonyms
+                .iter()
+                .any(|flags| !restricted.iter().flatten().any(|f| flags.contains(f)))
+        })
+    }
+
+    fn is_forbidden(&self, word: &str) -> bool {
+        self.forbidden.is_some_and(|flag| self.has_flag(word, flag))
+    }
+}
+
+impl HunspellStemFilter {
+    /// Creates a filter with a dictionary, it can be shared by several filters.
+    pub fn new(dictionary: impl Into<Arc<HunspellDictionary>>) -> Self {
+        HunspellStemFilter {
+            dictionary: dictionary.into(),
+        }
+    }
+}
+
+impl TokenFilter for HunspellStemFilter {
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let mut res = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            let mut stems = self.dictionary.stems(&token.text);
+            let lowercase = token.text.chars().all(|c| !c.is_uppercase());
+            if stems.is_empty() && lowercase {
+                stems = self.dictionary.stems(&capitalize(&token.text));
+                stems.iter_mut().for_each(|s| *s = s.to_lowercase());
+            }
+            if stems.is_empty() {
+                res.push(token);
+                continue;
+            }
+            res.extend(stems.into_iter().map(|text| Token {
+                text,
+                ..token.clone()
+            }));
+        }
+        res
+    }
+}
+
+fn decode(bytes: Vec<u8>, encoding: &str) -> io::Result<String> {
+    match encoding {
+        "UTF-8" | "UTF8" => String::from_utf8(bytes).map_err(|e| invalid(&e.to_string())),
+        "ISO8859-1" | "ISO-8859-1" => Ok(bytes.into_iter().map(char::from).collect()),
+        _ => {
+            let table = code_page(encoding)
+                .ok_or_else(|| invalid(&format!("unsupported encoding {encoding}")))?;
+            let decode = |b: u8| match b.checked_sub(0x80) {
+                Some(high) => table[usize::from(high)],
+                None => char::from(b),
+            };
+            Ok(bytes.into_iter().map(decode).collect())
+        }
+    }
+}
+
+fn flag_format(aff: &str) -> FlagFormat {
+    let format = aff
+        .lines()
+        .find_map(|line| line.strip_prefix("FLAG "))
+        .map(str::trim);
+    match format {
+        Some("long") => FlagFormat::Long,
+        Some("num") => FlagFormat::Num,
+        _ => FlagFormat::Char,
+    }
+}
+
+fn parse_flags(flags: &str, format: FlagFormat) -> io::Result<Vec<Flag>> {
+    match format {
+        FlagFormat::Char => Ok(flags.chars().map(Flag::from).collect()),
+        FlagFormat::Long => {
+            let chars: Vec<char> = flags.chars().collect();
+            Ok(chars
+                .chunks(2)
+                .map(|pair| pair.iter().fold(0, |flag, &c| flag << 16 | Flag::from(c)))
+                .collect())
+        }
+        FlagFormat::Num => flags
+            .split(',')
+            .map(|flag| flag.trim().parse().map_err(|_| invalid(flags)))
+            .collect(),
+    }
+}
+
+/// Parses flags, which are an index of flag aliases if the `.aff` file defines them.
+fn resolve_flags(flags: &str, format: FlagFormat, aliases: &[Vec<Flag>]) -> io::Result<Vec<Flag>> {
+    if aliases.is_empty() {
+        return parse_flags(flags, format);
+    }
+    flags
+        .parse::<usize>()
+        .ok()
+        .and_then(|i| aliases.get(i.checked_sub(1)?))
+        .cloned()
+        .ok_or_else(|| invalid(flags))
+}
+
+fn parse_flag(flag: &str, format: FlagFormat) -> io::Result<Flag> {
+    parse_flags(flag, format)?
+        .first()
+        .copied()
+        .ok_or_else(|| invalid(flag))
+}
+
+/// Parses a condition like "[^aeiou]y", "." matches any character.
+fn parse_condition(condition: &str) -> Vec<Pattern> {
+    let mut res = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        let pattern = match c {
+            '.' => Pattern::Any,
+            '[' => {
+                let class: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                match class.strip_prefix('^') {
+                    Some(class) => Pattern::Class {
+                        negated: true,
+                        chars: class.chars().collect(),
+                    },
+                    None => Pattern::Class {
+                        negated: false,
+                        chars: class.chars().collect(),
+                    },
+                }
+            }
+            c => Pattern::Char(c),
+        };
+        res.push(pattern);
+    }
+    if res.iter().all(|p| matches!(p, Pattern::Any)) {
+        res.clear();
+    }
+    res
+}
+
+fn matches(pattern: &Pattern, c: char) -> bool {
+    match pattern {
+        Pattern::Any => true,
+        Pattern::Char(expected) => *expected == c,
+        Pattern::Class { negated, chars } => chars.contains(&c) != *negated,
+    }
+}
+
+fn matches_start(condition: &[Pattern], word: &str) -> bool {
+    word.chars().count() >= condition.len()
+        && condition
+            .iter()
+            .zip(word.chars())
+            .all(|(p, c)| matches(p, c))
+}
+
+fn matches_end(condition: &[Pattern], word: &str) -> bool {
+    word.chars().count() >= condition.len()
+        && condition
+            .iter()
+            .rev()
+            .zip(word.chars().rev())
+            .all(|(p, c)| matches(p, c))
+}
+
+fn empty_if_zero(text: &str) -> &str {
+    if text == "0" {
+        ""
+    } else {
+        text
+    }
+}
+
+/// Returns a word and its lowercase and capitalized forms for capitalized and uppercase words.
+fn case_variants(word: &str) -> Vec<String> {
+    let mut res = vec![word.to_string()];
+    let mut chars = word.chars();
+    if chars.next().is_some_and(char::is_uppercase) {
+        if chars.any(char::is_lowercase) {
+            res.push(word.to_lowercase());
+        } else {
+            res.push(capitalize(&word.to_lowercase()));
+            res.push(word.to_lowercase());
+        }
+    }
+    res
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod hunspell_tests {
+    use crate::analyzer::Analyzer;
+    use crate::filters::LowercaseFilter;
+    use crate::hunspell::{HunspellDictionary, HunspellStemFilter};
+    use crate::tokenizer::Tokenizer;
+
+    const AFF: &str = "SET UTF-8
+TRY esianrtolcdugmphyw
+NEEDAFFIX X
+FORBIDDENWORD F
+REP 1
+REP f ph
+
+PFX U Y 1
+PFX U 0 un .
+
+SFX S Y 3
+SFX S y ies [^aeiou]y
+SFX S 0 s [aeiou]y
+SFX S 0 s [^y]
+
+SFX D Y 2
+SFX D 0 ed [^e]
+SFX D 0 d e
+
+SFX L Y 1
+SFX L 0 ful/Y .
+
+SFX Y Y 1
+SFX Y 0 ly .
+";
+
+    const DIC: &str = "8
+cherry/S
+day/S
+lock/UD
+hope/LD
+phone/S
+Paris
+thee/X
+foos/F
+";
+
+    fn dictionary() -> HunspellDictionary {
+        HunspellDictionary::from_readers(AFF.as_bytes(), DIC.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_stems() {
+        let dictionary = dictionary();
+        assert_eq!(dictionary.stems("cherries"), ["cherry"], "suffix failed");
+        assert_eq!(dictionary.stems("days"), ["day"], "suffix condition failed");
+        assert_eq!(dictionary.stems("hoped"), ["hope"], "suffix failed");
+        assert_eq!(
+            dictionary.stems("unlocked"),
+            ["lock"],
+            "cross product failed"
+        );
+        assert_eq!(
+            dictionary.stems("hopefully"),
+            ["hope"],
+            "two suffixes failed"
+        );
+        assert!(
+            dictionary.stems("cherrys").is_empty(),
+            "wrong suffix stemmed"
+        );
+    }
+
+    #[test]
+    fn test_empty_affix() {
+        let aff = "SFX A Y 1\nSFX A a 0 .\nPFX N Y 1\nPFX N u 0 .\n";
+        let dic = "2\nkniha/A\nukol/N\n";
+        let dictionary = HunspellDictionary::from_readers(aff.as_bytes(), dic.as_bytes()).unwrap();
+        assert_eq!(dictionary.stems("knih"), ["kniha"], "empty suffix failed");
+        assert_eq!(dictionary.stems("kniha"), ["kniha"], "word failed");
+        assert_eq!(dictionary.stems("kol"), ["ukol"], "empty prefix failed");
+    }
+
+    #[test]
+    fn test_code_pages() {
+        let aff = b"SET ISO8859-2\nSFX A Y 1\nSFX A 0 \xF9 .\n";
+        let dic = b"1\nd\xF9m/A\n";
+        let dictionary = HunspellDictionary::from_readers(&aff[..], &dic[..]).unwrap();
+        assert!(dictionary.check("dům"), "ISO8859-2 decoding failed");
+        assert_eq!(dictionary.stems("důmů"), ["dům"], "ISO8859-2 affix failed");
+
+        let dic = b"1\n\xC4\xCF\xCD\n";
+        let dictionary = HunspellDictionary::from_readers(&b"SET KOI8-R\n"[..], &dic[..]).unwrap();
+        assert!(dictionary.check("дом"), "KOI8-R decoding failed");
+
+        let aff = "SET UTF-16\n";
+        let res = HunspellDictionary::from_readers(aff.as_bytes(), &b"0\n"[..]);
+        assert!(res.is_err(), "unsupported encoding accepted");
+    }
+
+    #[test]
+    fn test_flag_aliases() {
+        let aff = "FLAG long\nAF 2\nAF Aa\nAF AaBb\nSFX Aa Y 1\nSFX Aa 0 s .\nSFX Bb Y 1\nSFX Bb 0 ed .\n";
+        let dic = "2\nwalk/2\ncat/1\n";
+        let dictionary = HunspellDictionary::from_readers(aff.as_bytes(), dic.as_bytes()).unwrap();
+        assert_eq!(dictionary.stems("walked"), ["walk"], "flag alias failed");
+        assert_eq!(dictionary.stems("cats"), ["cat"], "flag alias failed");
+        assert!(!dictionary.check("cated"), "wrong flag alias");
+    }
+
+    #[test]
+    fn test_check() {
+        let dictionary = dictionary();
+        assert!(dictionary.check("Cherries"), "capitalized word failed");
+        assert!(dictionary.check("PARIS"), "uppercase proper noun failed");
+        assert!(!dictionary.check("paris"), "proper noun is not capitalized");
+        assert!(!dictionary.check("thee"), "word needs an affix");
+        assert!(!dictionary.check("foos"), "word is forbidden");
+    }
+
+    #[test]
+    fn test_suggest() {
+        let dictionary = dictionary();
+        assert_eq!(dictionary.suggest("fone"), ["phone"], "replacement failed");
+        assert_eq!(dictionary.suggest("dya"), ["day"], "swap failed");
+        assert_eq!(dictionary.suggest("lcok"), ["lock"], "swap failed");
+        assert!(
+            dictionary.suggest("hopes").contains(&"hoped".to_string()),
+            "edit failed"
+        );
+    }
+
+    #[test]
+    fn test_stem_filter() {
+        let filter = HunspellStemFilter::new(dictionary());
+        let analyzer = Analyzer::custom(
+            Tokenizer::new(),
+            vec![Box::new(LowercaseFilter), Box::new(filter)],
+        );
+        let res: Vec<String> = analyzer.analyze("Cherries unlocked in Paris, unknowns");
+        let expected = ["cherry", "lock", "in", "paris", "unknowns"];
+        assert_eq!(res, expected, "hunspell stemming failed");
+    }
+}
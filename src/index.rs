@@ -1,24 +1,98 @@
 /// This is the place where the Inverted Index is implemented.
 use crate::analyzer::Analyzer;
+use crate::detect::LanguageDetector;
 use crate::filters::Language;
+use crate::query::Query;
+use rayon::prelude::*;
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 
 /// Currently, `InvertedIndex` implements with `HashMap<String, HashSet<u64>>`.
 /// With HashSet as a value, we can efficiently perform union, difference, intersection,
 /// and symmetric differences operations.
 /// Additionally, HashSet enables us to have distinct values for a given key.
+/// Terms of every document are kept too, so deleting a document and reading its terms
+/// don't scan the whole vocabulary.
+/// Terms of every field are kept only for documents with named fields, which may be
+/// boosted in queries.
 pub struct InvertedIndex {
     idx: HashMap<String, HashSet<u64>>,
     analyzer: Analyzer,
+    doc_lengths: HashMap<u64, usize>,
+    doc_terms: HashMap<u64, HashSet<String>>,
+    doc_fields: HashMap<u64, HashMap<String, HashSet<String>>>,
+    surface_forms: HashMap<String, HashMap<String, usize>>,
+    detection: Option<Detection>,
+}
+
+/// `Detection` routes every document to a sub-index of its detected language.
+/// `default`: is a language of documents kept in the index itself, including undetected ones
+/// `indexes`: are sub-indexes of other languages, every one has its own analyzer
+/// `languages`: are detected languages of documents
+struct Detection {
+    detector: LanguageDetector,
+    default: Language,
+    indexes: HashMap<Language, InvertedIndex>,
+    languages: HashMap<u64, Language>,
 }
 
 /// `Document` represents text that's needs to be indexed.
 /// `id`: is a unique text id
-/// `text`: is a text which will be divided into tokens and added to an index.
-#[derive(Clone)]
+/// `text`: is a text which will be divided into tokens and added to an index,
+/// it's the field named `TEXT_FIELD`
+/// `fields`: are other named texts, e.g. a title, which may be boosted in queries,
+/// see `Query::with_field_boosts`, they are added with `with_field`
+///
+/// Since fields were added, a document can't be created with a struct literal like
+/// `Document { id, text }`, use `Document::new(id, text)` instead.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Document {
     pub id: u64,
     pub text: String,
+    pub(crate) fields: Vec<(String, String)>,
+}
+
+/// Name of the field of `Document::text`.
+pub const TEXT_FIELD: &str = "text";
+
+/// `Page` selects a window of the ordered search results.
+/// `offset`: is a number of results to skip
+/// `limit`: is a maximum number of results, `None` returns all remaining results
+/// `search_after`: is a cursor, only results ordered after this doc id are returned
+///
+/// Results are ordered by doc id, so the last id of a page can be passed as
+/// `search_after` to fetch the next one.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Page {
+    pub offset: usize,
+    pub limit: Option<usize>,
+    pub search_after: Option<u64>,
+}
+
+impl Document {
+    /// Creates document without fields.
+    pub fn new(id: u64, text: impl Into<String>) -> Self {
+        Document {
+            id,
+            text: text.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Adds a named field, e.g. `Document::new(1, "body").with_field("title", "Title")`.
+    pub fn with_field(mut self, name: impl Into<String>, text: impl Into<String>) -> Self {
+        self.fields.push((name.into(), text.into()));
+        self
+    }
+
+    /// Returns names and texts of all fields, `text` first.
+    pub fn field_texts(&self) -> impl Iterator<Item = (&str, &str)> {
+        let fields = self
+            .fields
+            .iter()
+            .map(|(name, text)| (name.as_str(), text.as_str()));
+        [(TEXT_FIELD, self.text.as_str())].into_iter().chain(fields)
+    }
 }
 
 /// Creates empty index.
@@ -32,16 +106,63 @@ impl Default for InvertedIndex {
 impl InvertedIndex {
     /// Creates empty index with custom language.
     pub fn new(language: Language) -> Self {
+        Self::with_analyzer(Analyzer::new(language))
+    }
+
+    /// Creates empty index with custom analyzer.
+    pub fn with_analyzer(analyzer: Analyzer) -> Self {
         InvertedIndex {
             idx: HashMap::new(),
-            analyzer: Analyzer::new(language),
+            analyzer,
+            doc_lengths: HashMap::new(),
+            doc_terms: HashMap::new(),
+            doc_fields: HashMap::new(),
+            surface_forms: HashMap::new(),
+            detection: None,
+        }
+    }
+
+    /// Creates empty index which detects the language of every document,
+    /// and keeps it in a sub-index analyzed with stop words and stemming of that language.
+    /// Queries are analyzed for every language and searched in all sub-indexes.
+    /// Documents in undetected languages are analyzed as `default` language.
+    pub fn with_language_detection(detector: LanguageDetector, default: Language) -> Self {
+        InvertedIndex {
+            detection: Some(Detection {
+                detector,
+                default,
+                indexes: HashMap::new(),
+                languages: HashMap::new(),
+            }),
+            ..Self::new(default)
         }
     }
 
     /// Index document
+    /// A document with an already indexed id replaces its previous version.
     pub fn add(&mut self, docs: &[Document]) {
-        for doc in docs.iter() {
-            for token in self.analyzer.analyze(doc.text.as_str()) {
+        let docs = self.route(docs, false);
+        self.index(&docs);
+    }
+
+    /// Index documents in parallel.
+    /// Every thread analyzes its share of documents into a partial index,
+    /// then the partial indexes are merged into this one.
+    pub fn add_parallel(&mut self, docs: &[Document]) {
+        let docs = self.route(docs, true);
+        self.index_parallel(&docs);
+    }
+
+    fn index(&mut self, docs: &[&Document]) {
+        for doc in self.replaced(docs) {
+            let (terms, fields) = doc_terms_with_words(&self.analyzer, doc);
+            self.set_fields(doc.id, fields);
+            self.doc_lengths.insert(doc.id, terms.len());
+            self.doc_terms
+                .insert(doc.id, terms.keys().cloned().collect());
+            for (token, words) in terms {
+                let words = words.into_iter().map(|word| (word, 1));
+                count_words(&mut self.surface_forms, &token, words);
                 match self.idx.get_mut(&*token) {
                     None => {
                         let v = HashSet::from([doc.id]);
@@ -55,44 +176,518 @@ impl InvertedIndex {
         }
     }
 
-    /// Search text in index
-    pub fn search(&self, text: &str) -> HashSet<u64> {
-        let mut result: HashSet<u64> = HashSet::new();
-        for token in self.analyzer.analyze(text) {
-            match self.idx.get(&*token) {
-                None => {}
-                Some(ids) => {
-                    if result.is_empty() {
-                        result = ids.clone();
+    fn index_parallel(&mut self, docs: &[&Document]) {
+        let docs = self.replaced(docs);
+        let analyzer = &self.analyzer;
+        let (partial, doc_terms, forms) = docs
+            .par_iter()
+            .fold(
+                || (HashMap::new(), Vec::new(), HashMap::new()),
+                |(mut idx, mut doc_terms, mut forms): (
+                    HashMap<String, HashSet<u64>>,
+                    Vec<_>,
+                    _,
+                ),
+                 doc| {
+                    let (terms, fields) = doc_terms_with_words(analyzer, doc);
+                    let keys = terms.keys().cloned().collect::<Vec<_>>();
+                    doc_terms.push((doc.id, keys, fields));
+                    for (token, words) in terms {
+                        count_words(&mut forms, &token, words.into_iter().map(|w| (w, 1)));
+                        idx.entry(token).or_default().insert(doc.id);
                     }
-                    result = result.intersection(ids).copied().collect();
+                    (idx, doc_terms, forms)
+                },
+            )
+            .reduce(
+                || (HashMap::new(), Vec::new(), HashMap::new()),
+                |(a, mut a_terms, mut a_forms), (b, b_terms, b_forms)| {
+                    a_terms.extend(b_terms);
+                    for (token, words) in b_forms {
+                        count_words(&mut a_forms, &token, words);
+                    }
+                    (merge(a, b), a_terms, a_forms)
+                },
+            );
+        self.idx = merge(std::mem::take(&mut self.idx), partial);
+        for (id, terms, fields) in doc_terms {
+            self.set_fields(id, fields);
+            self.doc_lengths.insert(id, terms.len());
+            self.doc_terms.insert(id, terms.into_iter().collect());
+        }
+        for (token, words) in forms {
+            count_words(&mut self.surface_forms, &token, words);
+        }
+    }
+
+    /// Removes postings of already indexed documents, so a re-added document replaces
+    /// its previous version, returns the documents keeping only the last version of a
+    /// document added several times.
+    fn replaced<'a>(&mut self, docs: &[&'a Document]) -> Vec<&'a Document> {
+        let last: HashMap<u64, usize> = docs
+            .iter()
+            .enumerate()
+            .map(|(i, doc)| (doc.id, i))
+            .collect();
+        for &id in last.keys() {
+            if self.doc_lengths.contains_key(&id) {
+                self.remove_postings(id);
+            }
+        }
+        docs.iter()
+            .enumerate()
+            .filter(|(i, doc)| last[&doc.id] == *i)
+            .map(|(_, doc)| *doc)
+            .collect()
+    }
+
+    /// Keeps terms of every field of a document with named fields.
+    fn set_fields(&mut self, id: u64, fields: HashMap<String, HashSet<String>>) {
+        if fields.is_empty() {
+            self.doc_fields.remove(&id);
+        } else {
+            self.doc_fields.insert(id, fields);
+        }
+    }
+
+    /// Removes document from index.
+    pub fn delete(&mut self, id: u64) {
+        self.remove_postings(id);
+        if let Some(detection) = &mut self.detection {
+            let language = detection.languages.remove(&id);
+            if let Some(index) = language.and_then(|l| detection.indexes.get_mut(&l)) {
+                index.delete(id);
+            }
+        }
+    }
+
+    /// Removes document from this index, sub-indexes are not changed.
+    /// Surface forms of removed terms are dropped, counts of remaining terms are kept
+    /// as the index has no texts to subtract them.
+    fn remove_postings(&mut self, id: u64) {
+        for term in self.doc_terms.remove(&id).unwrap_or_default() {
+            let Some(ids) = self.idx.get_mut(&term) else {
+                continue;
+            };
+            ids.remove(&id);
+            if ids.is_empty() {
+                self.idx.remove(&term);
+                self.surface_forms.remove(&term);
+            }
+        }
+        self.doc_lengths.remove(&id);
+        self.doc_fields.remove(&id);
+    }
+
+    /// Returns the detected language of a document,
+    /// `None` if the document is unknown or the index doesn't detect languages.
+    pub fn language(&self, id: u64) -> Option<Language> {
+        let detection = self.detection.as_ref()?;
+        detection.languages.get(&id).copied()
+    }
+
+    /// Search text in index.
+    /// Returns ids of matched documents ordered by doc id.
+    pub fn search(&self, text: &str) -> Vec<u64> {
+        let mut ids = matches(&self.analyzer, text, |token| {
+            self.idx.get(token).map(Cow::Borrowed)
+        });
+        for index in self.sub_indexes() {
+            ids.extend(index.search(text));
+        }
+        ordered(ids)
+    }
+
+    /// Search text in index and return only the requested page of results.
+    pub fn search_page(&self, text: &str, page: &Page) -> Vec<u64> {
+        page.apply(self.search(text))
+    }
+
+    /// Search text in index and score matched documents with BM25.
+    /// Term frequencies are not stored, so every term counts once and shorter documents
+    /// with rarer terms score higher.
+    /// Words of the text may be boosted, e.g. "fox^3 dog", see `Query`.
+    /// Scores of every language are normalized to at most 1 in an index with language
+    /// detection, so results of different languages are comparable.
+    /// Returns ids with scores ordered by descending score, then by doc id.
+    pub fn search_scored(&self, text: &str) -> Vec<(u64, f64)> {
+        self.search_query(&Query::parse(text))
+    }
+
+    /// Search text in index like `search_scored`, but match documents containing any
+    /// of the terms, so documents with more and rarer or boosted terms rank higher.
+    pub fn search_scored_any(&self, text: &str) -> Vec<(u64, f64)> {
+        self.search_query_any(&Query::parse(text))
+    }
+
+    /// Search a parsed query like `search_scored`, fields of the query may be boosted,
+    /// e.g. `Query::parse("rust").with_field_boosts(&["title^2"])`.
+    /// A term counts once in every field containing it, weighted by the field boost.
+    pub fn search_query(&self, query: &Query) -> Vec<(u64, f64)> {
+        self.ranked(query, false)
+    }
+
+    /// Search a parsed query like `search_scored_any`, see `search_query`.
+    pub fn search_query_any(&self, query: &Query) -> Vec<(u64, f64)> {
+        self.ranked(query, true)
+    }
+
+    fn ranked(&self, query: &Query, any: bool) -> Vec<(u64, f64)> {
+        let mut res = self.scored(query, any);
+        if self.detection.is_some() {
+            normalize(&mut res);
+            for index in self.sub_indexes() {
+                let mut scored = index.scored(query, any);
+                normalize(&mut scored);
+                res.extend(scored);
+            }
+        }
+        res.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        res
+    }
+
+    /// Scores documents of this index only, sub-indexes are not searched.
+    /// `any`: matches documents containing any term instead of all terms
+    pub(crate) fn scored(&self, query: &Query, any: bool) -> Vec<(u64, f64)> {
+        let terms = query.terms(&self.analyzer);
+        let ids: HashSet<u64> = if any {
+            terms
+                .iter()
+                .filter_map(|(term, _)| self.idx.get(term))
+                .flatten()
+                .copied()
+                .collect()
+        } else {
+            matches(&self.analyzer, query.text(), |token| {
+                self.idx.get(token).map(Cow::Borrowed)
+            })
+        };
+        if ids.is_empty() {
+            return Vec::new();
+        }
+        let bm25 = self.scorer();
+        ids.into_iter()
+            .map(|id| {
+                let score = terms
+                    .iter()
+                    .filter_map(|(term, boost)| Some((term, self.idx.get(term)?, boost)))
+                    .filter(|(_, ids, _)| ids.contains(&id))
+                    .map(|(term, ids, boost)| {
+                        let tf = self
+                            .term_fields(id, term)
+                            .map(|f| query.field_boost(f))
+                            .sum();
+                        boost * bm25(id, ids.len(), tf)
+                    })
+                    .sum();
+                (id, score)
+            })
+            .collect()
+    }
+
+    /// Returns a function scoring a document with BM25 for a term found in `doc_freq`
+    /// documents with a term frequency `tf`, 1 for a term of a document without fields.
+    pub(crate) fn scorer(&self) -> impl Fn(u64, usize, f64) -> f64 + '_ {
+        let num_docs = self.doc_lengths.len();
+        let avg_length = self.avg_length();
+        move |id, doc_freq, tf| {
+            let length = self.doc_length(id).unwrap_or_default() as f64;
+            idf(doc_freq, num_docs) * tf_norm(tf, length, avg_length)
+        }
+    }
+
+    /// Returns names of fields of a document containing a term, ordered by name,
+    /// only `TEXT_FIELD` for a document without named fields.
+    pub(crate) fn term_fields<'a>(
+        &'a self,
+        id: u64,
+        term: &'a str,
+    ) -> impl Iterator<Item = &'a str> {
+        let fields = self.doc_fields.get(&id).map(|fields| {
+            let mut names: Vec<&str> = fields
+                .iter()
+                .filter(|(_, terms)| terms.contains(term))
+                .map(|(name, _)| name.as_str())
+                .collect();
+            names.sort_unstable();
+            names
+        });
+        fields.unwrap_or_else(|| vec![TEXT_FIELD]).into_iter()
+    }
+
+    /// Returns a number of distinct terms of a document in this index.
+    pub(crate) fn doc_length(&self, id: u64) -> Option<usize> {
+        self.doc_lengths.get(&id).copied()
+    }
+
+    /// Returns an average number of distinct terms of documents in this index.
+    pub(crate) fn avg_length(&self) -> f64 {
+        self.doc_lengths.values().sum::<usize>() as f64 / self.doc_lengths.len() as f64
+    }
+
+    /// Records detected languages and moves documents of other than default language
+    /// to their sub-indexes, returns documents of the default language.
+    /// A re-added document detected as another language is removed from the index of
+    /// its previous language.
+    fn route<'a>(&mut self, docs: &'a [Document], parallel: bool) -> Vec<&'a Document> {
+        let Some(detection) = &mut self.detection else {
+            return docs.iter().collect();
+        };
+        let detector = &detection.detector;
+        let detect = |doc: &Document| detector.detect(&doc.text);
+        let detected: Vec<Option<Language>> = if parallel {
+            docs.par_iter().map(detect).collect()
+        } else {
+            docs.iter().map(detect).collect()
+        };
+
+        let mut groups: HashMap<Language, Vec<&Document>> = HashMap::new();
+        let mut own = Vec::new();
+        let mut moved = Vec::new();
+        for (doc, language) in docs.iter().zip(detected) {
+            let language = language.unwrap_or(detection.default);
+            let previous = detection.languages.insert(doc.id, language);
+            if let Some(previous) = previous.filter(|&previous| previous != language) {
+                moved.push((doc.id, previous));
+            }
+            if language == detection.default {
+                own.push(doc);
+            } else {
+                groups.entry(language).or_default().push(doc);
+            }
+        }
+        for (language, docs) in groups {
+            let index = detection
+                .indexes
+                .entry(language)
+                .or_insert_with(|| InvertedIndex::new(language));
+            if parallel {
+                index.index_parallel(&docs);
+            } else {
+                index.index(&docs);
+            }
+        }
+        let default = detection.default;
+        for (id, previous) in moved {
+            if previous == default {
+                self.remove_postings(id);
+            } else if let Some(index) = self
+                .detection
+                .as_mut()
+                .and_then(|detection| detection.indexes.get_mut(&previous))
+            {
+                index.delete(id);
+            }
+        }
+        own
+    }
+
+    pub(crate) fn analyzer(&self) -> &Analyzer {
+        &self.analyzer
+    }
+
+    /// Returns a number of documents in this index, sub-indexes are not counted.
+    pub(crate) fn num_docs(&self) -> usize {
+        self.doc_lengths.len()
+    }
+
+    /// Returns ids of documents containing a term in this index, sub-indexes are not searched.
+    pub(crate) fn postings(&self, term: &str) -> Option<&HashSet<u64>> {
+        self.idx.get(term)
+    }
+
+    /// Returns terms of a document in this index, sub-indexes are not searched.
+    pub(crate) fn terms_of(&self, id: u64) -> Vec<&str> {
+        self.doc_terms
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Returns the sub-index keeping documents of a language, or this index for the
+    /// default language, unknown languages and an index without language detection.
+    pub(crate) fn language_index(&self, language: Option<Language>) -> &InvertedIndex {
+        self.detection
+            .as_ref()
+            .zip(language)
+            .and_then(|(detection, language)| detection.indexes.get(&language))
+            .unwrap_or(self)
+    }
+
+    /// Returns whether documents are kept in sub-indexes by language.
+    pub(crate) fn has_language_detection(&self) -> bool {
+        self.detection.is_some()
+    }
+
+    /// Detects the language of a text, `None` for an index without language detection.
+    pub(crate) fn detect(&self, text: &str) -> Option<Language> {
+        self.detection.as_ref()?.detector.detect(text)
+    }
+
+    /// Returns terms of this index with numbers of documents containing them,
+    /// sub-indexes are not included.
+    pub(crate) fn vocabulary(&self) -> HashMap<&str, usize> {
+        self.idx
+            .iter()
+            .map(|(term, ids)| (term.as_str(), ids.len()))
+            .collect()
+    }
+
+    /// Returns the lowercase word a term was most often produced from, e.g. "lazy" for "lazi".
+    pub(crate) fn surface_form(&self, term: &str) -> Option<&str> {
+        let words = self.surface_forms.get(term)?;
+        words
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+            .map(|(word, _)| word.as_str())
+    }
+
+    fn sub_indexes(&self) -> impl Iterator<Item = &InvertedIndex> {
+        self.detection.iter().flat_map(|d| d.indexes.values())
+    }
+}
+
+impl Page {
+    /// Cuts the page out of results ordered by doc id.
+    pub(crate) fn apply(&self, ids: Vec<u64>) -> Vec<u64> {
+        ids.into_iter()
+            .filter(|&id| self.search_after.is_none_or(|after| id > after))
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+/// Collects ids of documents containing all tokens of the text, boosts are ignored.
+/// `postings` returns ids of documents containing a token.
+pub(crate) fn matches<'a, F>(analyzer: &Analyzer, text: &str, postings: F) -> HashSet<u64>
+where
+    F: Fn(&str) -> Option<Cow<'a, HashSet<u64>>>,
+{
+    let mut result: HashSet<u64> = HashSet::new();
+    for (token, _) in Query::parse(text).terms(analyzer) {
+        match postings(&token) {
+            None => {}
+            Some(ids) => {
+                if result.is_empty() {
+                    result = ids.into_owned();
+                } else {
+                    result = result.intersection(&ids).copied().collect();
                 }
             }
         }
+    }
+
+    result
+}
 
-        result
+/// BM25 term frequency saturation and document length normalization parameters.
+pub(crate) const K1: f64 = 1.2;
+pub(crate) const B: f64 = 0.75;
+
+/// BM25 inverse document frequency of a term found in `doc_freq` of `num_docs` documents.
+pub(crate) fn idf(doc_freq: usize, num_docs: usize) -> f64 {
+    let (doc_freq, num_docs) = (doc_freq as f64, num_docs as f64);
+    (1.0 + (num_docs - doc_freq + 0.5) / (doc_freq + 0.5)).ln()
+}
+
+/// BM25 term frequency normalization of a term with frequency `tf` in a document of
+/// `length` terms.
+pub(crate) fn tf_norm(tf: f64, length: f64, avg_length: f64) -> f64 {
+    (K1 + 1.0) * tf / (tf + K1 * (1.0 - B + B * length / avg_length))
+}
+
+/// Divides scores by the highest one.
+fn normalize(scores: &mut [(u64, f64)]) {
+    let max = scores.iter().map(|(_, score)| *score).fold(0.0, f64::max);
+    if max > 0.0 {
+        scores.iter_mut().for_each(|(_, score)| *score /= max);
+    }
+}
+
+/// Returns distinct terms of all fields of a document with the distinct lowercase words
+/// producing them, and terms of every field of a document with named fields.
+fn doc_terms_with_words(
+    analyzer: &Analyzer,
+    doc: &Document,
+) -> (
+    HashMap<String, HashSet<String>>,
+    HashMap<String, HashSet<String>>,
+) {
+    let mut res = terms_with_words(analyzer, &doc.text);
+    let mut fields = HashMap::new();
+    if doc.fields.is_empty() {
+        return (res, fields);
+    }
+    fields.insert(TEXT_FIELD.to_string(), res.keys().cloned().collect());
+    for (name, text) in &doc.fields {
+        let terms = terms_with_words(analyzer, text);
+        let field: &mut HashSet<String> = fields.entry(name.clone()).or_default();
+        field.extend(terms.keys().cloned());
+        for (term, words) in terms {
+            res.entry(term).or_default().extend(words);
+        }
+    }
+    (res, fields)
+}
+
+/// Returns distinct terms of a text with the distinct lowercase words producing them.
+fn terms_with_words(analyzer: &Analyzer, text: &str) -> HashMap<String, HashSet<String>> {
+    let mut res: HashMap<String, HashSet<String>> = HashMap::new();
+    for token in analyzer.token_stream(text) {
+        let word = text.get(token.offset.clone()).unwrap_or(&token.text);
+        let word = word.to_lowercase();
+        res.entry(token.text).or_default().insert(word);
     }
+    res
+}
+
+/// Adds numbers of documents with words producing a term.
+fn count_words<I>(forms: &mut HashMap<String, HashMap<String, usize>>, term: &str, words: I)
+where
+    I: IntoIterator<Item = (String, usize)>,
+{
+    let counts = forms.entry(term.to_string()).or_default();
+    for (word, count) in words {
+        *counts.entry(word).or_default() += count;
+    }
+}
+
+/// Orders matched ids by doc id.
+pub(crate) fn ordered(ids: HashSet<u64>) -> Vec<u64> {
+    let mut result: Vec<u64> = ids.into_iter().collect();
+    result.sort_unstable();
+    result
+}
+
+/// Merges two partial indexes, the smaller one is merged into the larger one.
+pub(crate) fn merge(
+    a: HashMap<String, HashSet<u64>>,
+    b: HashMap<String, HashSet<u64>>,
+) -> HashMap<String, HashSet<u64>> {
+    let (mut large, small) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    for (token, ids) in small {
+        large.entry(token).or_default().extend(ids);
+    }
+    large
 }
 
 #[cfg(test)]
 mod index_tests {
-    use crate::index::{Document, InvertedIndex};
-    use std::collections::HashSet;
+    use crate::analyzer::Analyzer;
+    use crate::detect::LanguageDetector;
+    use crate::filters::{Language, LowercaseFilter};
+    use crate::index::{Document, InvertedIndex, Page};
+    use crate::ngram::NGramFilter;
+    use crate::test_support::{docs, fox_docs};
+    use crate::tokenizer::Tokenizer;
 
     #[test]
     fn add_test() {
         let mut idx = InvertedIndex::default();
-        let doc = [
-            Document {
-                id: 1,
-                text: "The quick brown fox jumped over the lazy dog".to_string(),
-            },
-            Document {
-                id: 2,
-                text: "Quick brown foxes leap over lazy dogs in summer".to_string(),
-            },
-        ];
-        idx.add(&doc);
+        idx.add(&fox_docs());
         let number_of_keys = idx.idx.keys().len();
         assert_eq!(number_of_keys, 9, "adding to index failed");
     }
@@ -100,36 +695,219 @@ mod index_tests {
     #[test]
     fn search_on_one_phrase_test() {
         let mut idx = InvertedIndex::default();
-        let doc = [
-            Document {
-                id: 1,
-                text: "The quick brown fox jumped over the lazy dog".to_string(),
-            },
-            Document {
-                id: 2,
-                text: "Quick brown foxes leap over lazy dogs in summer".to_string(),
-            },
-        ];
-        idx.add(&doc);
+        idx.add(&fox_docs());
         let result = idx.search("dogs in summer");
-        assert_eq!(result.get(&2), Some(&2), "searching on one phrase failed");
+        assert!(result.contains(&2), "searching on one phrase failed");
     }
 
     #[test]
     fn intersection_search_test() {
         let mut idx = InvertedIndex::default();
-        let doc = [
-            Document {
-                id: 1,
-                text: "The quick brown fox jumped over the lazy dog".to_string(),
-            },
-            Document {
-                id: 2,
-                text: "Quick brown foxes leap over lazy dogs in summer".to_string(),
-            },
-        ];
+        idx.add(&fox_docs());
+        let result = idx.search("brown foxes");
+        assert_eq!(result, [1, 2], "intersection search failed");
+    }
+
+    #[test]
+    fn add_parallel_test() {
+        let doc = (0..100)
+            .map(|id| {
+                Document::new(
+                    id,
+                    format!("Quick brown foxes leap over lazy dogs {id} times"),
+                )
+            })
+            .collect::<Vec<Document>>();
+        let mut serial = InvertedIndex::default();
+        serial.add(&doc);
+        let mut parallel = InvertedIndex::default();
+        parallel.add_parallel(&doc);
+        assert_eq!(serial.idx, parallel.idx, "parallel indexing failed");
+    }
+
+    #[test]
+    fn infix_search_test() {
+        let analyzer = Analyzer::custom(
+            Tokenizer::new(),
+            vec![Box::new(LowercaseFilter), Box::new(NGramFilter::new(3, 3))],
+        );
+        let mut idx = InvertedIndex::with_analyzer(analyzer);
+        idx.add(&fox_docs());
+        assert_eq!(idx.search("ummer"), [2], "infix search failed");
+        assert_eq!(idx.search("rown"), [1, 2], "infix search failed");
+    }
+
+    #[test]
+    fn delete_test() {
+        let mut idx = InvertedIndex::default();
+        idx.add(&fox_docs());
+        idx.delete(2);
+        assert_eq!(idx.search("brown foxes"), [1], "delete failed");
+        assert!(!idx.idx.contains_key("summer"), "empty postings are kept");
+    }
+
+    #[test]
+    fn ordered_search_test() {
+        let mut idx = InvertedIndex::default();
+        let doc = [5, 3, 9, 1]
+            .map(|id| Document::new(id, "Quick brown foxes leap over lazy dogs in summer"));
         idx.add(&doc);
         let result = idx.search("brown foxes");
-        assert_eq!(result, HashSet::from([1, 2]), "intersection search failed");
+        assert_eq!(result, [1, 3, 5, 9], "results are not ordered by doc id");
+    }
+
+    #[test]
+    fn search_page_test() {
+        let mut idx = InvertedIndex::default();
+        let doc = [5, 3, 9, 1, 7]
+            .map(|id| Document::new(id, "The quick brown fox jumped over the lazy dog"));
+        idx.add(&doc);
+
+        let page = Page {
+            offset: 1,
+            limit: Some(2),
+            search_after: None,
+        };
+        let result = idx.search_page("lazy dog", &page);
+        assert_eq!(result, [3, 5], "offset and limit paging failed");
+
+        let page = Page {
+            offset: 0,
+            limit: Some(2),
+            search_after: result.last().copied(),
+        };
+        let result = idx.search_page("lazy dog", &page);
+        assert_eq!(result, [7, 9], "search after paging failed");
+    }
+
+    #[test]
+    fn language_detection_test() {
+        let detector =
+            LanguageDetector::new([Language::English, Language::German, Language::Russian]);
+        let mut idx = InvertedIndex::with_language_detection(detector, Language::English);
+        idx.add_parallel(&docs(&[
+            "The children were playing in the gardens",
+            "Die Kinder spielten in den Gärten",
+            "Дети играли в садах",
+        ]));
+
+        assert_eq!(
+            idx.language(1),
+            Some(Language::English),
+            "english is not detected"
+        );
+        assert_eq!(
+            idx.language(2),
+            Some(Language::German),
+            "german is not detected"
+        );
+        assert_eq!(
+            idx.language(3),
+            Some(Language::Russian),
+            "russian is not detected"
+        );
+        assert_eq!(idx.search("the garden"), [1], "english query failed");
+        assert_eq!(idx.search("die Kinder"), [2], "german query failed");
+        assert_eq!(idx.search("дети играли"), [3], "russian query failed");
+
+        idx.delete(3);
+        assert_eq!(
+            idx.language(3),
+            None,
+            "language of deleted document is kept"
+        );
+    }
+
+    #[test]
+    fn scored_search_test() {
+        let mut idx = InvertedIndex::default();
+        idx.add(&docs(&[
+            "Rust language with a long list of features and many other things",
+            "Rust language",
+            "Python language",
+        ]));
+
+        let result = idx.search_scored("rust language");
+        let ids: Vec<u64> = result.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, [2, 1], "shorter document is not ranked first");
+        assert!(result[0].1 > result[1].1, "scores are not ordered");
+    }
+
+    #[test]
+    fn language_sub_indexes_test() {
+        let detector = LanguageDetector::new([Language::English, Language::German]);
+        let mut idx = InvertedIndex::with_language_detection(detector, Language::English);
+        idx.add(&docs(&[
+            "Rust is a fast language",
+            "Rust is a fast and safe language for the systems of the future",
+            "Rust ist eine schnelle Sprache",
+        ]));
+
+        assert_eq!(idx.search("rust"), [1, 2, 3], "fan out search failed");
+        let result = idx.search_scored("rust");
+        let expected = [(1, 1.0), (3, 1.0)];
+        assert_eq!(
+            result[..2],
+            expected,
+            "scores are not normalized per language"
+        );
+        assert!(
+            result[2].0 == 2 && result[2].1 < 1.0,
+            "scores are not merged"
+        );
+
+        idx.delete(3);
+        assert_eq!(idx.search("rust"), [1, 2], "delete from sub-index failed");
+    }
+
+    #[test]
+    fn language_change_test() {
+        let detector = LanguageDetector::new([Language::English, Language::German]);
+        let mut idx = InvertedIndex::with_language_detection(detector, Language::English);
+        let doc = |text: &str| Document::new(1, text.to_string());
+        idx.add(&[doc("The children were playing in the gardens")]);
+        idx.add(&[doc("Die Kinder spielten in den Gärten")]);
+        assert_eq!(
+            idx.language(1),
+            Some(Language::German),
+            "language not updated"
+        );
+        assert!(idx.search("playing").is_empty(), "stale english postings");
+        assert_eq!(idx.num_docs(), 0, "stale english document is counted");
+        assert_eq!(idx.search("Kinder"), [1], "german postings missing");
+
+        idx.add(&[doc("The children were playing in the gardens")]);
+        assert!(idx.search("Kinder").is_empty(), "stale german postings");
+        assert_eq!(
+            idx.language_index(Some(Language::German)).num_docs(),
+            0,
+            "stale german document is counted"
+        );
+        assert_eq!(idx.search("playing"), [1], "english postings missing");
+    }
+
+    #[test]
+    fn re_add_test() {
+        let mut idx = InvertedIndex::default();
+        idx.add(&[Document::new(1, "brown fox")]);
+        idx.add(&[Document::new(1, "lazy dog")]);
+        assert!(idx.search("fox").is_empty(), "stale postings");
+        assert_eq!(idx.search("dog"), [1], "new postings missing");
+        assert_eq!(idx.terms_of(1).len(), 2, "stale document terms");
+
+        let mut parallel = InvertedIndex::default();
+        parallel.add_parallel(&[Document::new(1, "brown fox")]);
+        parallel.add_parallel(&[Document::new(1, "lazy dog"), Document::new(1, "quick cat")]);
+        assert!(parallel.search("fox").is_empty(), "stale parallel postings");
+        assert!(
+            parallel.search("dog").is_empty(),
+            "earlier version in batch kept"
+        );
+        assert_eq!(parallel.search("cat"), [1], "parallel postings missing");
+        assert_eq!(
+            parallel.terms_of(1).len(),
+            2,
+            "stale parallel document terms"
+        );
     }
 }
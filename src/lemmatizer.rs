@@ -0,0 +1,189 @@
+/// Lemmatization reduces words to their dictionary form with a lemma dictionary.
+/// Unlike stemming it handles irregular forms ("went" becomes "go") and doesn't conflate
+/// unrelated words ("university" and "universe" stay different).
+/// Words missing from the dictionary can fall back to stemming.
+use crate::filters::{Language, StemmingFilter, TokenFilter};
+use crate::tokenizer::Token;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Bundled dictionary of irregular English word forms.
+const ENGLISH: &str = include_str!("lemmas_english.txt");
+
+/// `LemmaDictionary` maps word forms to their lemmas, a form may have several lemmas,
+/// e.g. "leaves" is a form of "leaf" and "leave".
+/// Every lemma is also a form of itself.
+#[derive(Clone, Debug, Default)]
+pub struct LemmaDictionary {
+    lemmas: HashMap<String, Vec<String>>,
+}
+
+/// `LemmatizerFilter` replaces tokens with their lemmas at the same position.
+/// Tokens missing from the dictionary are stemmed if a stemming fallback is set,
+/// otherwise they are kept as is.
+pub struct LemmatizerFilter {
+    dictionary: Arc<LemmaDictionary>,
+    fallback: Option<StemmingFilter>,
+}
+
+impl LemmaDictionary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a dictionary from a file, see `from_reader` for the format.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads a dictionary where every line is a lemma followed by its forms,
+    /// separated by tabs or spaces, e.g. "go goes went gone going".
+    /// This also reads lists with a lemma and a single form per line.
+    /// Empty lines and lines starting with "#" are skipped, words are lowercased.
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut dictionary = Self::new();
+        for line in reader.lines() {
+            dictionary.add_line(&line?);
+        }
+        Ok(dictionary)
+    }
+
+    /// Returns the bundled dictionary of a language, only English has one.
+    /// It covers irregular forms, regular ones are left to stemming.
+    pub fn bundled(language: &Language) -> Option<Self> {
+        let lines = match language {
+            Language::English => ENGLISH,
+            _ => return None,
+        };
+        let mut dictionary = Self::new();
+        lines.lines().for_each(|line| dictionary.add_line(line));
+        Some(dictionary)
+    }
+
+    /// Adds a form of a lemma.
+    pub fn insert(&mut self, form: &str, lemma: &str) {
+        for form in [lemma, form] {
+            let lemmas = self.lemmas.entry(form.to_string()).or_default();
+            if !lemmas.iter().any(|l| l == lemma) {
+                lemmas.push(lemma.to_string());
+            }
+        }
+    }
+
+    /// Returns lemmas of a word form.
+    pub fn lemmas(&self, form: &str) -> Option<&[String]> {
+        self.lemmas.get(form).map(Vec::as_slice)
+    }
+
+    fn add_line(&mut self, line: &str) {
+        if line.starts_with('#') {
+            return;
+        }
+        let mut words = line.split_whitespace().map(str::to_lowercase);
+        let Some(lemma) = words.next() else {
+            return;
+        };
+        self.insert(&lemma, &lemma);
+        for form in words {
+            self.insert(&form, &lemma);
+        }
+    }
+}
+
+impl LemmatizerFilter {
+    /// Creates a filter with a dictionary, it can be shared by several filters.
+    pub fn new(dictionary: impl Into<Arc<LemmaDictionary>>) -> Self {
+        LemmatizerFilter {
+            dictionary: dictionary.into(),
+            fallback: None,
+        }
+    }
+
+    /// Stems tokens missing from the dictionary with the stemming algorithm of a language.
+    pub fn with_stemming_fallback(mut self, language: &Language) -> Self {
+        self.fallback = Some(StemmingFilter::new(language));
+        self
+    }
+}
+
+impl TokenFilter for LemmatizerFilter {
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let mut res = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            match self.dictionary.lemmas(&token.text) {
+                Some(lemmas) => res.extend(lemmas.iter().map(|lemma| Token {
+                    text: lemma.clone(),
+                    ..token.clone()
+                })),
+                None => match &self.fallback {
+                    Some(stemming) => res.push(Token {
+                        text: stemming.stem(&token.text),
+                        ..token
+                    }),
+                    None => res.push(token),
+                },
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod lemmatizer_tests {
+    use crate::analyzer::Analyzer;
+    use crate::filters::{Language, TokenFilter};
+    use crate::lemmatizer::{LemmaDictionary, LemmatizerFilter};
+    use crate::tokenizer::{Token, Tokenize, Tokenizer};
+
+    fn texts(tokens: Vec<Token>) -> Vec<String> {
+        tokens.into_iter().map(|t| t.text).collect()
+    }
+
+    #[test]
+    fn test_lemmatize_irregular() {
+        let dictionary = LemmaDictionary::bundled(&Language::English).unwrap();
+        let filter = LemmatizerFilter::new(dictionary);
+        let res = texts(filter.filter(Tokenizer::new().tokens("went geese better walked")));
+        assert_eq!(
+            res,
+            ["go", "goose", "good", "walked"],
+            "lemmatization failed"
+        );
+    }
+
+    #[test]
+    fn test_lemmatize_ambiguous() {
+        let dictionary = LemmaDictionary::bundled(&Language::English).unwrap();
+        let filter = LemmatizerFilter::new(dictionary);
+        let tokens = filter.filter(Tokenizer::new().tokens("leaves"));
+        let res: Vec<(String, usize)> = tokens.into_iter().map(|t| (t.text, t.position)).collect();
+        let expected = [("leave", 0), ("leaf", 0)].map(|(t, p)| (t.to_string(), p));
+        assert_eq!(res, expected, "ambiguous form failed");
+    }
+
+    #[test]
+    fn test_read_dictionary() {
+        let text = "# lemma and forms\nuniversity\tuniversities\n\nuniverse universes\n";
+        let dictionary = LemmaDictionary::from_reader(text.as_bytes()).unwrap();
+        let filter = LemmatizerFilter::new(dictionary).with_stemming_fallback(&Language::English);
+        let res = texts(filter.filter(Tokenizer::new().tokens("universities universe worked")));
+        assert_eq!(
+            res,
+            ["university", "universe", "work"],
+            "stemming fallback failed"
+        );
+    }
+
+    #[test]
+    fn test_analyze_lemmas() {
+        let dictionary = LemmaDictionary::bundled(&Language::English).unwrap();
+        let lemmatizer =
+            LemmatizerFilter::new(dictionary).with_stemming_fallback(&Language::English);
+        let analyzer = Analyzer::with_lemmatizer(Language::English, lemmatizer);
+        let res: Vec<String> = analyzer.analyze("The children went running");
+        assert_eq!(res, ["child", "go", "run"], "lemmatizing analyzer failed");
+    }
+}
@@ -1,5 +1,29 @@
 pub mod analyzer;
-mod filters;
+pub mod case_fold;
+pub mod char_filters;
+pub mod cjk;
+mod code_pages;
+pub mod compound;
+pub mod detect;
+pub mod explain;
+pub mod filters;
+pub mod hunspell;
 pub mod index;
+pub mod lemmatizer;
+mod light_stemmers;
+pub mod merge_policy;
+pub mod more_like_this;
+pub mod ngram;
+pub mod normalize;
+pub mod percolator;
+pub mod phonetic;
+pub mod query;
+mod segment;
+pub mod special;
+pub mod spelling;
 mod stopwords;
-mod tokenizer;
+#[cfg(test)]
+mod test_support;
+pub mod tokenizer;
+pub mod wal;
+pub mod writer;
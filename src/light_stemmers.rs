@@ -0,0 +1,598 @@
+/// Light stemmers for languages without a Snowball algorithm in `rust_stemmers`.
+/// They are not Snowball stemmers: every one removes common inflections with the steps
+/// and regions of the Snowball algorithm of its language, see
+/// https://snowballstem.org/algorithms/ and the links on every function, but with shorter
+/// suffix lists, so stems differ from the Snowball output for rarer forms.
+/// Affixes are removed in steps, most of them only inside a region at the end of a word,
+/// so short words are left intact.
+///
+/// Removes suffixes of the longest match first, a match outside of the region is not removed.
+fn remove_suffix(word: &mut String, region: usize, suffixes: &[&str]) -> bool {
+    let longest = suffixes
+        .iter()
+        .filter(|s| word.ends_with(**s) && word.len() > s.len())
+        .max_by_key(|s| s.len());
+    match longest {
+        Some(suffix) if word.len() - suffix.len() >= region => {
+            word.truncate(word.len() - suffix.len());
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Returns the byte index after the first non-vowel following a vowel, starting at `from`.
+fn region(word: &str, vowels: &str, from: usize) -> usize {
+    let mut after_vowel = false;
+    for (i, c) in word[from..].char_indices() {
+        let vowel = vowels.contains(c);
+        if after_vowel && !vowel {
+            return from + i + c.len_utf8();
+        }
+        after_vowel = vowel;
+    }
+    word.len()
+}
+
+/// Returns the byte index after the first vowel.
+fn after_vowel(word: &str, vowels: &str) -> usize {
+    word.char_indices()
+        .find(|(_, c)| vowels.contains(*c))
+        .map_or(word.len(), |(i, c)| i + c.len_utf8())
+}
+
+const ARMENIAN_VOWELS: &str = "աեէիոըօւ";
+
+/// Armenian nouns add plural, case and article endings, in that order.
+const ARMENIAN_ENDINGS: &[&str] = &[
+    "ներ",
+    "եր",
+    "ների",
+    "երի",
+    "ներին",
+    "երին",
+    "ներում",
+    "երում",
+    "ներից",
+    "երից",
+    "ներով",
+    "երով",
+    "ում",
+    "ից",
+    "ով",
+    "ին",
+    "ի",
+    "ության",
+    "ություն",
+    "ել",
+    "ալ",
+    "ելու",
+    "ալու",
+    "եց",
+    "աց",
+    "եցին",
+    "ացին",
+];
+
+/// Based on https://snowballstem.org/algorithms/armenian/stemmer.html
+/// Endings are removed after the first vowel (RV).
+pub(crate) fn armenian(word: &str) -> String {
+    let mut word = word.to_string();
+    let rv = after_vowel(&word, ARMENIAN_VOWELS);
+    remove_suffix(&mut word, rv, &["ը"]);
+    remove_suffix(&mut word, rv, ARMENIAN_ENDINGS);
+    word
+}
+
+const BASQUE_VOWELS: &str = "aeiou";
+
+/// Basque case endings, stems ending with a consonant add an "e" before some of them,
+/// it stays with the stem.
+const BASQUE_ENDINGS: &[&str] = &[
+    "a", "ak", "ek", "ok", "ari", "ei", "aren", "ren", "rekin", "arekin", "ekin", "rentzat",
+    "arentzat", "entzat", "tik", "tatik", "etatik", "ko", "tako", "etako", "ra", "tara", "etara",
+    "an", "tan", "etan",
+];
+
+/// Based on https://snowballstem.org/algorithms/basque/stemmer.html
+/// Case endings are removed inside R1.
+pub(crate) fn basque(word: &str) -> String {
+    let mut word = word.to_string();
+    let r1 = region(&word, BASQUE_VOWELS, 0);
+    // Endings are stacked, e.g. "etxekoa" is "etxe" + "ko" + "a".
+    for _ in 0..2 {
+        if !remove_suffix(&mut word, r1, BASQUE_ENDINGS) {
+            break;
+        }
+    }
+    word
+}
+
+const CATALAN_VOWELS: &str = "aeiouáàéèíïóòúü";
+
+const CATALAN_STANDARD: &[&str] = &[
+    "ament", "aments", "ació", "acions", "ador", "adora", "adores", "adors", "ança", "ances",
+    "ància", "ències", "ència", "isme", "ismes", "ista", "istes", "itat", "itats", "able", "ables",
+    "ible", "ibles", "ós", "osa", "oses", "osos", "ment", "ments", "iu", "iva", "ives", "ius",
+    "eria", "eries",
+];
+
+const CATALAN_VERB: &[&str] = &[
+    "ar", "er", "ir", "re", "ava", "aves", "aven", "àvem", "àveu", "ant", "ent", "int", "at",
+    "ada", "ats", "ades", "it", "ida", "its", "ides", "ut", "uda", "uts", "udes", "aré", "aràs",
+    "arà", "arem", "areu", "aran", "aria", "aries", "arien", "eixo", "eixes", "eix", "eixen", "em",
+    "eu", "en",
+];
+
+const CATALAN_RESIDUAL: &[&str] = &["a", "e", "i", "o", "s", "as", "es", "is", "os", "ó", "à"];
+
+/// Based on https://snowballstem.org/algorithms/catalan/stemmer.html
+/// Standard or verb suffixes, then a residual suffix are removed inside R1.
+pub(crate) fn catalan(word: &str) -> String {
+    let mut word = word.to_string();
+    let r1 = region(&word, CATALAN_VOWELS, 0);
+    if !remove_suffix(&mut word, r1, CATALAN_STANDARD) {
+        remove_suffix(&mut word, r1, CATALAN_VERB);
+    }
+    remove_suffix(&mut word, r1, CATALAN_RESIDUAL);
+    word.replace("l·l", "ll")
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' => 'a',
+            'é' | 'è' => 'e',
+            'í' | 'ï' => 'i',
+            'ó' | 'ò' => 'o',
+            'ú' | 'ü' => 'u',
+            c => c,
+        })
+        .collect()
+}
+
+/// Indonesian stemming removes particles, possessive pronouns, prefixes and suffixes
+/// while a word has more than two vowels.
+/// Which suffixes may be removed depends on the removed prefix.
+/// Based on https://snowballstem.org/algorithms/indonesian/stemmer.html, which implements
+/// F. Z. Tala, "A Study of Stemming Effects on Information Retrieval in Bahasa Indonesia", 2003.
+pub(crate) fn indonesian(word: &str) -> String {
+    let mut word = word.to_string();
+    let mut measure = word.chars().filter(|c| "aeiou".contains(*c)).count();
+    if measure <= 2 {
+        return word;
+    }
+    if remove_suffix(&mut word, 0, &["kah", "lah", "pun"]) {
+        measure -= 1;
+    }
+    if measure > 2 && remove_suffix(&mut word, 0, &["ku", "mu", "nya"]) {
+        measure -= 1;
+    }
+    if measure <= 2 {
+        return word;
+    }
+    match indonesian_first_prefix(&mut word) {
+        Some(prefix) => {
+            measure -= 1;
+            if measure > 2 && indonesian_suffix(&mut word, prefix) {
+                measure -= 1;
+                if measure > 2 {
+                    indonesian_second_prefix(&mut word);
+                }
+            }
+        }
+        None => {
+            let prefix = match indonesian_second_prefix(&mut word) {
+                Some(prefix) => {
+                    measure -= 1;
+                    prefix
+                }
+                None => Prefix::None,
+            };
+            if measure > 2 {
+                indonesian_suffix(&mut word, prefix);
+            }
+        }
+    }
+    word
+}
+
+/// Kinds of Indonesian prefixes, they restrict suffixes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Prefix {
+    None,
+    Di,
+    Ber,
+    Ke,
+}
+
+fn indonesian_first_prefix(word: &mut String) -> Option<Prefix> {
+    let prefixes = [
+        "meny", "peny", "meng", "peng", "mem", "pem", "men", "pen", "ter", "di", "me", "ke",
+    ];
+    let prefix = prefixes.into_iter().find(|p| word.starts_with(p))?;
+    let vowel_follows = word[prefix.len()..]
+        .chars()
+        .next()
+        .is_some_and(|c| "aeiou".contains(c));
+    let (replacement, kind) = match prefix {
+        "meny" | "peny" if !vowel_follows => return None,
+        "meny" => ("s", Prefix::Di),
+        "peny" => ("s", Prefix::Ke),
+        "mem" if vowel_follows => ("p", Prefix::Di),
+        "pem" if vowel_follows => ("p", Prefix::Ke),
+        "peng" | "pem" | "pen" | "ke" => ("", Prefix::Ke),
+        _ => ("", Prefix::Di),
+    };
+    word.replace_range(..prefix.len(), replacement);
+    Some(kind)
+}
+
+fn indonesian_second_prefix(word: &mut String) -> Option<Prefix> {
+    if let Some(rest) = word
+        .strip_prefix("belajar")
+        .or(word.strip_prefix("pelajar"))
+    {
+        *word = format!("ajar{rest}");
+        return Some(Prefix::Ber);
+    }
+    let mut chars = word.chars().skip(2);
+    // "be" is a prefix before a consonant other than "r" followed by "er".
+    let ker = chars.next().is_some_and(|c| !"aeiour".contains(c))
+        && chars.next() == Some('e')
+        && chars.next() == Some('r');
+    let prefix = ["ber", "per", "pe", "be"]
+        .into_iter()
+        .find(|p| word.starts_with(p) && (*p != "be" || ker))?;
+    word.replace_range(..prefix.len(), "");
+    Some(Prefix::Ber)
+}
+
+fn indonesian_suffix(word: &mut String, prefix: Prefix) -> bool {
+    let allowed = if word.ends_with("kan") {
+        !matches!(prefix, Prefix::Ke | Prefix::Ber)
+    } else if word.ends_with("an") {
+        prefix != Prefix::Di
+    } else if word.ends_with('i') {
+        prefix != Prefix::Ke && !word.ends_with("si")
+    } else {
+        false
+    };
+    allowed && remove_suffix(word, 0, &["kan", "an", "i"])
+}
+
+const IRISH_VOWELS: &str = "aeiouáéíóú";
+
+/// Initial mutations of Irish words and the letters they replace.
+const IRISH_MUTATIONS: &[(&str, &str)] = &[
+    ("bhf", "f"),
+    ("bh", "b"),
+    ("ch", "c"),
+    ("dh", "d"),
+    ("fh", "f"),
+    ("gh", "g"),
+    ("mh", "m"),
+    ("ph", "p"),
+    ("sh", "s"),
+    ("th", "t"),
+    ("mb", "b"),
+    ("gc", "c"),
+    ("nd", "d"),
+    ("ng", "g"),
+    ("bp", "p"),
+    ("dt", "d"),
+    ("ts", "s"),
+    ("h-", ""),
+    ("n-", ""),
+    ("t-", ""),
+];
+
+const IRISH_NOUN: &[&str] = &[
+    "anna", "eanna", "acha", "eacha", "aí", "aithe", "ithe", "ín", "íní",
+];
+
+const IRISH_DERIVATIONAL: &[&str] = &[
+    "acht", "eacht", "íocht", "aíocht", "úil", "úla", "óir", "eoir",
+];
+
+const IRISH_VERB: &[&str] = &[
+    "imid", "aimid", "ímid", "aímid", "adh", "eadh", "faidh", "fidh", "tear", "tar", "ann", "eann",
+];
+
+/// Based on https://snowballstem.org/algorithms/irish/stemmer.html
+/// Initial mutations are undone, then noun, derivational and verb suffixes are removed.
+pub(crate) fn irish(word: &str) -> String {
+    let mut word = word.to_string();
+    let mutation = IRISH_MUTATIONS
+        .iter()
+        .find(|(prefix, _)| word.starts_with(prefix) && word.len() > prefix.len() + 1);
+    if let Some((prefix, letter)) = mutation {
+        word.replace_range(..prefix.len(), letter);
+    }
+    let r1 = region(&word, IRISH_VOWELS, 0);
+    let r2 = region(&word, IRISH_VOWELS, r1);
+    remove_suffix(&mut word, r1, IRISH_NOUN);
+    remove_suffix(&mut word, r2, IRISH_DERIVATIONAL);
+    remove_suffix(&mut word, r1, IRISH_VERB);
+    word
+}
+
+const LITHUANIAN_VOWELS: &str = "aeiyouąęėįųū";
+
+const LITHUANIAN_ENDINGS: &[&str] = &[
+    "as", "is", "ys", "us", "a", "e", "o", "u", "ė", "ą", "ę", "į", "ų", "ių", "ui", "iui", "ai",
+    "iai", "ei", "ams", "iams", "ėms", "oms", "ioms", "ims", "ums", "iems", "omis", "ėmis", "imis",
+    "umis", "iais", "ais", "os", "ios", "ės", "ies", "ose", "iose", "ėse", "yse", "uose", "iuose",
+    "yje", "oje", "ioje", "ėje", "uje", "iuje", "iu", "ti", "tis", "au", "iau", "ome", "ame",
+    "ote", "ate", "si",
+];
+
+/// Based on https://snowballstem.org/algorithms/lithuanian/stemmer.html
+/// Endings are removed inside R1.
+pub(crate) fn lithuanian(word: &str) -> String {
+    let mut word = word.to_string();
+    let r1 = region(&word, LITHUANIAN_VOWELS, 0);
+    if remove_suffix(&mut word, r1, LITHUANIAN_ENDINGS) {
+        // Endings soften "t" and "d" before them.
+        if word.ends_with('č') {
+            word.pop();
+            word.push('t');
+        } else if word.ends_with("dž") {
+            word.pop();
+        }
+    }
+    word
+}
+
+/// Nepali case markers and postpositions which are written together with nouns.
+const NEPALI_MARKERS: &[&str] = &[
+    "लाई",
+    "ले",
+    "बाट",
+    "को",
+    "का",
+    "की",
+    "कै",
+    "मा",
+    "मै",
+    "द्वारा",
+    "हरु",
+    "हरू",
+    "सँग",
+    "संग",
+    "सित",
+    "देखि",
+    "पछि",
+    "भन्दा",
+    "तिर",
+    "तर्फ",
+    "माथि",
+    "मुनि",
+    "नै",
+    "मात्र",
+    "लगायत",
+    "अनुसार",
+];
+
+const NEPALI_VERB: &[&str] = &[
+    "छ",
+    "छन्",
+    "छु",
+    "छौं",
+    "थियो",
+    "थिए",
+    "्छ",
+    "्छन्",
+    "्छु",
+    "ेको",
+    "ेका",
+    "ेकी",
+    "ेर",
+    "्नु",
+    "्ने",
+];
+
+/// Based on https://snowballstem.org/algorithms/nepali/stemmer.html
+/// Case markers and postpositions, then verb endings are removed.
+pub(crate) fn nepali(word: &str) -> String {
+    let mut word = word.to_string();
+    // Step 1: markers, which may be stacked, e.g. "घरहरूमा" is "घर" + "हरू" + "मा".
+    // A verb ending is left to step 2 even if it ends with a marker, e.g. "गरेको" is
+    // "गर" + "ेको", not "गरे" + "को".
+    let ends_with_verb = |word: &str| {
+        NEPALI_VERB
+            .iter()
+            .any(|s| word.ends_with(s) && word.len() > s.len())
+    };
+    while !ends_with_verb(&word) && remove_suffix(&mut word, 0, NEPALI_MARKERS) {}
+    // Step 2: a verb ending, e.g. "गरेकोमा" is "गर" + "ेको" + "मा".
+    remove_suffix(&mut word, 0, NEPALI_VERB);
+    word
+}
+
+const YIDDISH_VOWELS: &str = "אעיו";
+
+const YIDDISH_ENDINGS: &[&str] = &[
+    "ן", "ען", "ס", "עס", "ער", "ערס", "ענס", "ט", "עט", "סט", "עסט", "טע", "ע", "ים", "ות",
+    "קייט", "הייט", "לעך", "עלע",
+];
+
+/// Final forms of Hebrew letters used at the end of words.
+const FINAL_FORMS: [(char, char); 5] = [('כ', 'ך'), ('מ', 'ם'), ('נ', 'ן'), ('פ', 'ף'), ('צ', 'ץ')];
+
+/// Based on https://snowballstem.org/algorithms/yiddish/stemmer.html
+/// The past participle prefix and endings are removed, endings inside R1.
+pub(crate) fn yiddish(word: &str) -> String {
+    // Ligatures are spelled out and points are removed.
+    let mut word: String = word
+        .replace('װ', "וו")
+        .replace('ױ', "וי")
+        .replace('ײ', "יי")
+        .chars()
+        .filter(|c| !('\u{05B0}'..='\u{05C7}').contains(c))
+        .collect();
+    // Past participles have the "גע" prefix.
+    if word.starts_with("גע") && (word.ends_with('ט') || word.ends_with('ן')) {
+        let stem = &word["גע".len()..];
+        if stem.chars().count() > 3 {
+            word = stem.to_string();
+        }
+    }
+    let min = word.char_indices().nth(3).map_or(word.len(), |(i, _)| i);
+    let r1 = region(&word, YIDDISH_VOWELS, 0).max(min);
+    if remove_suffix(&mut word, r1, YIDDISH_ENDINGS) {
+        let last = word.pop();
+        let final_form = FINAL_FORMS.iter().find(|(c, _)| Some(*c) == last);
+        word.extend(final_form.map(|(_, f)| *f).or(last));
+    }
+    word
+}
+
+#[cfg(test)]
+mod light_stemmers_tests {
+    use crate::light_stemmers::{
+        armenian, basque, catalan, indonesian, irish, lithuanian, nepali, yiddish,
+    };
+
+    fn assert_stems(stem: fn(&str) -> String, expected: &[(&str, &str)]) {
+        for (word, expected) in expected {
+            assert_eq!(stem(word), *expected, "stemming {word} failed");
+        }
+    }
+
+    #[test]
+    fn test_armenian() {
+        let expected = [
+            ("քաղաքներից", "քաղաք"),
+            ("դպրոցում", "դպրոց"),
+            ("ազատություն", "ազատ"),
+            ("խոսեցին", "խոս"),
+            ("տուն", "տուն"),
+            ("քաղաքներ", "քաղաք"),
+            ("քաղաքում", "քաղաք"),
+            ("քաղաքը", "քաղաք"),
+            ("քաղաքները", "քաղաք"),
+        ];
+        assert_stems(armenian, &expected);
+    }
+
+    #[test]
+    fn test_basque() {
+        let expected = [
+            ("etxekoa", "etxe"),
+            ("etxetik", "etxe"),
+            ("gizonarekin", "gizon"),
+            ("herrira", "herri"),
+            ("umeentzat", "ume"),
+            ("mendi", "mendi"),
+            ("mendia", "mendi"),
+            ("mendiak", "mendi"),
+            ("menditik", "mendi"),
+            ("mendira", "mendi"),
+            ("mendiko", "mendi"),
+        ];
+        assert_stems(basque, &expected);
+    }
+
+    #[test]
+    fn test_catalan() {
+        let expected = [
+            ("ràpidament", "rapid"),
+            ("nacionalisme", "nacional"),
+            ("felicitat", "felic"),
+            ("parlarem", "parl"),
+            ("llibres", "llibr"),
+            ("col·lecció", "collecci"),
+            ("cantar", "cant"),
+            ("cantava", "cant"),
+            ("cantaven", "cant"),
+            ("cantat", "cant"),
+        ];
+        assert_stems(catalan, &expected);
+    }
+
+    #[test]
+    fn test_indonesian() {
+        let expected = [
+            ("permainan", "main"),
+            ("menyapu", "sapu"),
+            ("pembaca", "baca"),
+            ("mengambil", "ambil"),
+            ("dibacakan", "baca"),
+            ("kekuatan", "kuat"),
+            ("pelajaran", "ajar"),
+            ("bukunya", "buku"),
+            ("makanlah", "makan"),
+            ("mobil", "mobil"),
+            ("makanan", "makan"),
+            ("dimakan", "makan"),
+            ("makannya", "makan"),
+        ];
+        assert_stems(indonesian, &expected);
+    }
+
+    #[test]
+    fn test_irish() {
+        let expected = [
+            ("gcapall", "capall"),
+            ("bhfear", "fear"),
+            ("tseomra", "seomra"),
+            ("n-athair", "athair"),
+            ("gcathracha", "cathr"),
+            ("bhád", "bád"),
+            ("mbád", "bád"),
+            ("bádanna", "bád"),
+            ("bád", "bád"),
+        ];
+        assert_stems(irish, &expected);
+    }
+
+    #[test]
+    fn test_lithuanian() {
+        let expected = [
+            ("vaikas", "vaik"),
+            ("knygose", "knyg"),
+            ("žmonėms", "žmon"),
+            ("miestų", "miest"),
+            ("medžiai", "med"),
+            ("bėgti", "bėg"),
+            ("namas", "nam"),
+            ("namo", "nam"),
+            ("namui", "nam"),
+            ("namą", "nam"),
+            ("namuose", "nam"),
+            ("namų", "nam"),
+        ];
+        assert_stems(lithuanian, &expected);
+    }
+
+    #[test]
+    fn test_nepali() {
+        let expected = [
+            ("नेपालको", "नेपाल"),
+            ("केटाहरूलाई", "केटा"),
+            ("गर्छ", "गर"),
+            ("गरेको", "गर"),
+            ("गरेकोमा", "गर"),
+            ("म", "म"),
+            ("घरमा", "घर"),
+            ("घरहरू", "घर"),
+            ("घरहरूमा", "घर"),
+            ("घरबाट", "घर"),
+        ];
+        assert_stems(nepali, &expected);
+    }
+
+    #[test]
+    fn test_yiddish() {
+        let expected = [
+            ("קינדער", "קינד"),
+            ("שרײַבן", "שרייב"),
+            ("געשריבן", "שריב"),
+            ("געמאכט", "מאך"),
+            ("שיינקייט", "שיין"),
+            ("רעדן", "רעד"),
+            ("רעדט", "רעד"),
+            ("רעדסט", "רעד"),
+            ("גערעדט", "רעד"),
+        ];
+        assert_stems(yiddish, &expected);
+    }
+}
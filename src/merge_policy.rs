@@ -0,0 +1,219 @@
+/// Merge policies decide which index segments are merged together.
+/// Merging keeps the number of segments a search has to visit small
+/// and purges deleted documents from the merged segments.
+use std::mem;
+
+/// `SegmentInfo` describes a segment for a merge policy.
+/// `num_docs`: is a number of documents stored in the segment, including deleted ones
+/// `num_deleted`: is a number of deleted documents
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SegmentInfo {
+    pub num_docs: usize,
+    pub num_deleted: usize,
+}
+
+impl SegmentInfo {
+    /// Number of documents that are not deleted.
+    pub fn num_live(&self) -> usize {
+        self.num_docs - self.num_deleted
+    }
+}
+
+/// `MergePolicy` selects segments which should be merged.
+pub trait MergePolicy: Send {
+    /// Returns groups of segments to merge.
+    /// `segments` are ordered from the oldest to the newest,
+    /// every group is a list of indexes into `segments` and contains each segment at most once.
+    fn merges(&self, segments: &[SegmentInfo]) -> Vec<Vec<usize>>;
+}
+
+/// `NoMergePolicy` never merges segments.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoMergePolicy;
+
+impl MergePolicy for NoMergePolicy {
+    fn merges(&self, _segments: &[SegmentInfo]) -> Vec<Vec<usize>> {
+        Vec::new()
+    }
+}
+
+/// `LogSizeMergePolicy` assigns every segment a level by the logarithm of its size
+/// and merges `merge_factor` adjacent segments of the same level into one segment of the next level.
+/// `merge_factor`: is a number of segments merged at once
+/// `min_merge_docs`: segments smaller than this are treated as the same lowest level
+#[derive(Clone, Copy, Debug)]
+pub struct LogSizeMergePolicy {
+    pub merge_factor: usize,
+    pub min_merge_docs: usize,
+}
+
+impl Default for LogSizeMergePolicy {
+    fn default() -> Self {
+        LogSizeMergePolicy {
+            merge_factor: 10,
+            min_merge_docs: 1000,
+        }
+    }
+}
+
+impl LogSizeMergePolicy {
+    fn level(&self, info: &SegmentInfo) -> u32 {
+        let size = info.num_live().max(self.min_merge_docs).max(1);
+        (size as f64).log(self.merge_factor.max(2) as f64).floor() as u32
+    }
+}
+
+impl MergePolicy for LogSizeMergePolicy {
+    fn merges(&self, segments: &[SegmentInfo]) -> Vec<Vec<usize>> {
+        let merge_factor = self.merge_factor.max(2);
+        let mut merges = Vec::new();
+        let mut run: Vec<usize> = Vec::new();
+        for (i, info) in segments.iter().enumerate() {
+            if run
+                .first()
+                .is_some_and(|&first| self.level(&segments[first]) != self.level(info))
+            {
+                run.clear();
+            }
+            run.push(i);
+            if run.len() == merge_factor {
+                merges.push(mem::take(&mut run));
+            }
+        }
+        merges
+    }
+}
+
+/// `TieredMergePolicy` computes how many segments an index of the current size may have
+/// and merges the smallest segments once that budget is exceeded.
+/// Segments with too many deleted documents are rewritten on their own.
+/// `segments_per_tier`: is a number of segments allowed on every size tier
+/// `max_merge_at_once`: is a maximum number of segments merged at once
+/// `floor_segment_docs`: segments smaller than this are treated as being of this size
+/// `deletes_pct_allowed`: is a percentage of deleted documents that triggers a rewrite
+#[derive(Clone, Copy, Debug)]
+pub struct TieredMergePolicy {
+    pub segments_per_tier: usize,
+    pub max_merge_at_once: usize,
+    pub floor_segment_docs: usize,
+    pub deletes_pct_allowed: f64,
+}
+
+impl Default for TieredMergePolicy {
+    fn default() -> Self {
+        TieredMergePolicy {
+            segments_per_tier: 10,
+            max_merge_at_once: 10,
+            floor_segment_docs: 1000,
+            deletes_pct_allowed: 20.0,
+        }
+    }
+}
+
+impl TieredMergePolicy {
+    /// Number of segments allowed for an index with `total` live documents.
+    fn allowed_segments(&self, total: usize) -> usize {
+        let per_tier = self.segments_per_tier.max(1);
+        let mut tier_size = self.floor_segment_docs.max(1);
+        let mut remaining = total;
+        let mut allowed = 0;
+        loop {
+            let count = remaining.div_ceil(tier_size);
+            if count <= per_tier {
+                allowed += count;
+                break;
+            }
+            allowed += per_tier;
+            remaining -= per_tier * tier_size;
+            tier_size *= self.max_merge_at_once.max(2);
+        }
+        allowed.max(per_tier)
+    }
+}
+
+impl MergePolicy for TieredMergePolicy {
+    fn merges(&self, segments: &[SegmentInfo]) -> Vec<Vec<usize>> {
+        let mut merges = Vec::new();
+        let mut candidates = Vec::new();
+        for (i, info) in segments.iter().enumerate() {
+            let deleted_pct = info.num_deleted as f64 * 100.0 / info.num_docs.max(1) as f64;
+            if info.num_deleted > 0 && deleted_pct > self.deletes_pct_allowed {
+                merges.push(vec![i]);
+            } else {
+                candidates.push(i);
+            }
+        }
+
+        let total = candidates.iter().map(|&i| segments[i].num_live()).sum();
+        if candidates.len() > self.allowed_segments(total) {
+            candidates.sort_by_key(|&i| (segments[i].num_live(), i));
+            candidates.truncate(self.max_merge_at_once.max(2));
+            candidates.sort_unstable();
+            merges.push(candidates);
+        }
+        merges
+    }
+}
+
+#[cfg(test)]
+mod merge_policy_tests {
+    use crate::merge_policy::{LogSizeMergePolicy, MergePolicy, SegmentInfo, TieredMergePolicy};
+
+    fn segments(sizes: &[usize]) -> Vec<SegmentInfo> {
+        sizes
+            .iter()
+            .map(|&num_docs| SegmentInfo {
+                num_docs,
+                num_deleted: 0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn log_size_merges_same_level_test() {
+        let policy = LogSizeMergePolicy {
+            merge_factor: 3,
+            min_merge_docs: 1,
+        };
+        let res = policy.merges(&segments(&[30, 1, 2, 2, 10]));
+        assert_eq!(res, [vec![1, 2, 3]], "log size merge failed");
+    }
+
+    #[test]
+    fn log_size_skips_mixed_levels_test() {
+        let policy = LogSizeMergePolicy {
+            merge_factor: 3,
+            min_merge_docs: 1,
+        };
+        let res = policy.merges(&segments(&[1, 30, 2, 10, 2]));
+        assert!(res.is_empty(), "segments of different levels merged");
+    }
+
+    #[test]
+    fn tiered_merges_smallest_test() {
+        let policy = TieredMergePolicy {
+            segments_per_tier: 2,
+            max_merge_at_once: 2,
+            floor_segment_docs: 10,
+            deletes_pct_allowed: 20.0,
+        };
+        let res = policy.merges(&segments(&[5, 1, 7, 2]));
+        assert_eq!(res, [vec![1, 3]], "tiered merge failed");
+    }
+
+    #[test]
+    fn tiered_within_budget_test() {
+        let policy = TieredMergePolicy::default();
+        let res = policy.merges(&segments(&[5, 1, 7, 2]));
+        assert!(res.is_empty(), "segments merged within budget");
+    }
+
+    #[test]
+    fn tiered_rewrites_deleted_test() {
+        let policy = TieredMergePolicy::default();
+        let mut infos = segments(&[100, 100]);
+        infos[1].num_deleted = 50;
+        let res = policy.merges(&infos);
+        assert_eq!(res, [vec![1]], "segment with deletes is not rewritten");
+    }
+}
@@ -0,0 +1,171 @@
+/// More-like-this finds documents similar to a document or a text.
+/// The most distinctive terms of the source are selected by TF-IDF, then documents
+/// containing any of them are scored with BM25 weighted by the distinctiveness of terms.
+/// The index doesn't store term frequencies, so every term of an indexed document counts once.
+use crate::index::{idf, InvertedIndex};
+use std::collections::HashMap;
+
+/// `MoreLikeThis` selects query terms of the source.
+/// `min_term_freq`: is a minimal number of occurrences of a term in the source text,
+/// it's not applied to indexed documents, where every term counts once
+/// `min_doc_freq`: is a minimal number of documents containing a term
+/// `max_query_terms`: is a maximal number of terms with the highest TF-IDF in the query
+#[derive(Clone, Copy, Debug)]
+pub struct MoreLikeThis {
+    pub min_term_freq: usize,
+    pub min_doc_freq: usize,
+    pub max_query_terms: usize,
+}
+
+impl Default for MoreLikeThis {
+    fn default() -> Self {
+        MoreLikeThis {
+            min_term_freq: 1,
+            min_doc_freq: 1,
+            max_query_terms: 25,
+        }
+    }
+}
+
+impl InvertedIndex {
+    /// Finds documents similar to an indexed document, the document itself is excluded.
+    /// Terms found in no other document are skipped, they can't match anything.
+    /// `min_term_freq` is ignored, the index doesn't store term frequencies.
+    /// Returns ids with scores ordered by descending score, then by doc id.
+    pub fn more_like_doc(&self, id: u64, params: &MoreLikeThis) -> Vec<(u64, f64)> {
+        let index = self.language_index(self.language(id));
+        let term_freqs = index
+            .terms_of(id)
+            .into_iter()
+            .filter(|term| index.postings(term).is_some_and(|ids| ids.len() > 1))
+            .map(|term| (term.to_string(), 1))
+            .collect();
+        let params = MoreLikeThis {
+            min_term_freq: 1,
+            ..*params
+        };
+        let mut res = index.more_like(term_freqs, &params);
+        res.retain(|(doc_id, _)| *doc_id != id);
+        res
+    }
+
+    /// Finds documents similar to a text, it is analyzed like a query.
+    /// In an index with language detection only documents of the language of the text
+    /// are searched.
+    /// Returns ids with scores ordered by descending score, then by doc id.
+    pub fn more_like_text(&self, text: &str, params: &MoreLikeThis) -> Vec<(u64, f64)> {
+        let index = self.language_index(self.detect(text));
+        let mut term_freqs: HashMap<String, usize> = HashMap::new();
+        for token in index.analyzer().token_stream(text) {
+            *term_freqs.entry(token.text).or_default() += 1;
+        }
+        index.more_like(term_freqs, params)
+    }
+
+    /// Runs a disjunctive query of the most distinctive terms, each term is weighted by
+    /// its TF-IDF relative to the highest one.
+    fn more_like(
+        &self,
+        term_freqs: HashMap<String, usize>,
+        params: &MoreLikeThis,
+    ) -> Vec<(u64, f64)> {
+        let num_docs = self.num_docs();
+        let mut terms: Vec<(&str, f64)> = term_freqs
+            .iter()
+            .filter(|(_, &freq)| freq >= params.min_term_freq)
+            .filter_map(|(term, &freq)| {
+                let doc_freq = self.postings(term)?.len();
+                (doc_freq >= params.min_doc_freq)
+                    .then(|| (term.as_str(), freq as f64 * idf(doc_freq, num_docs)))
+            })
+            .collect();
+        terms.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+        terms.truncate(params.max_query_terms);
+        let Some(&(_, max_weight)) = terms.first() else {
+            return Vec::new();
+        };
+
+        let bm25 = self.scorer();
+        let mut scores: HashMap<u64, f64> = HashMap::new();
+        for (term, weight) in terms {
+            let Some(ids) = self.postings(term) else {
+                continue;
+            };
+            for &id in ids {
+                *scores.entry(id).or_default() += weight / max_weight * bm25(id, ids.len(), 1.0);
+            }
+        }
+        let mut res: Vec<(u64, f64)> = scores.into_iter().collect();
+        res.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        res
+    }
+}
+
+#[cfg(test)]
+mod more_like_this_tests {
+    use crate::index::InvertedIndex;
+    use crate::more_like_this::MoreLikeThis;
+    use crate::test_support::docs;
+
+    fn index() -> InvertedIndex {
+        let mut idx = InvertedIndex::default();
+        idx.add(&docs(&[
+            "Rust is a systems programming language with memory safety",
+            "Memory safety without garbage collection makes Rust a systems language",
+            "Python is a programming language with garbage collection",
+            "Gardening tips for growing tomatoes in summer",
+        ]));
+        idx
+    }
+
+    fn ids(res: Vec<(u64, f64)>) -> Vec<u64> {
+        res.into_iter().map(|(id, _)| id).collect()
+    }
+
+    #[test]
+    fn test_more_like_doc() {
+        let idx = index();
+        let res = ids(idx.more_like_doc(1, &MoreLikeThis::default()));
+        assert_eq!(res, [2, 3], "similar documents failed");
+        let params = MoreLikeThis {
+            min_term_freq: 2,
+            ..MoreLikeThis::default()
+        };
+        let res = ids(idx.more_like_doc(1, &params));
+        assert_eq!(res, [2, 3], "min term freq applied to a document");
+    }
+
+    #[test]
+    fn test_more_like_text() {
+        let idx = index();
+        let params = MoreLikeThis::default();
+        let res = ids(idx.more_like_text("growing tomatoes and more tomatoes", &params));
+        assert_eq!(res, [4], "similar text failed");
+
+        let params = MoreLikeThis {
+            min_term_freq: 2,
+            ..params
+        };
+        let res = ids(idx.more_like_text("rust safety, python and python", &params));
+        assert_eq!(res, [3], "min term freq failed");
+    }
+
+    #[test]
+    fn test_query_terms_limits() {
+        let idx = index();
+        let params = MoreLikeThis {
+            min_doc_freq: 3,
+            ..MoreLikeThis::default()
+        };
+        let mut res = ids(idx.more_like_text("python language", &params));
+        res.sort_unstable();
+        assert_eq!(res, [1, 2, 3], "min doc freq failed");
+
+        let params = MoreLikeThis {
+            max_query_terms: 1,
+            ..MoreLikeThis::default()
+        };
+        let res = ids(idx.more_like_text("python language", &params));
+        assert_eq!(res, [3], "max query terms failed");
+    }
+}
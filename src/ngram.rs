@@ -0,0 +1,448 @@
+/// N-gram tokenizers and filters split words into character n-grams.
+/// N-grams enable infix search ("rown" matches "brown"),
+/// edge n-grams are prefixes of words and enable search-as-you-type ("bro" matches "brown").
+/// Shingles are word n-grams, they make documents with the words of a query in the same order
+/// match better ("quick brown" doesn't match "brown and quick").
+use crate::filters::TokenFilter;
+use crate::tokenizer::{Token, Tokenize, Tokenizer};
+
+/// `NGramFilter` replaces every token with its character n-grams.
+/// N-grams share the position of the token they are produced from.
+/// `min_gram`: is a minimal n-gram length in characters
+/// `max_gram`: is a maximal n-gram length in characters
+/// `preserve_original`: keeps the token itself, even if it's shorter than `min_gram`
+#[derive(Clone, Copy, Debug)]
+pub struct NGramFilter {
+    pub min_gram: usize,
+    pub max_gram: usize,
+    pub preserve_original: bool,
+}
+
+/// `EdgeNGramFilter` replaces every token with its prefixes.
+/// Prefixes share the position of the token they are produced from.
+/// `min_gram`: is a minimal prefix length in characters
+/// `max_gram`: is a maximal prefix length in characters
+/// `preserve_original`: keeps the token itself, even if it's shorter than `min_gram`
+#[derive(Clone, Copy, Debug)]
+pub struct EdgeNGramFilter {
+    pub min_gram: usize,
+    pub max_gram: usize,
+    pub preserve_original: bool,
+}
+
+/// `ShingleFilter` adds word n-grams made of adjacent tokens joined with a space.
+/// A shingle gets the position of its first token, only the first token at every position
+/// is used to build shingles.
+/// `min_shingle_size`: is a minimal number of tokens in a shingle, at least 2
+/// `max_shingle_size`: is a maximal number of tokens in a shingle
+/// `output_unigrams`: keeps the tokens themselves
+/// `filler_token`: replaces removed tokens, e.g. stop words, in shingles, a shingle may
+/// start or end with it, e.g. "_ fox" for "the fox", but is never made only of it;
+/// without it shingles never span removed tokens
+#[derive(Clone, Debug)]
+pub struct ShingleFilter {
+    pub min_shingle_size: usize,
+    pub max_shingle_size: usize,
+    pub output_unigrams: bool,
+    pub filler_token: Option<String>,
+}
+
+/// `NGramTokenizer` splits words into character n-grams, every n-gram gets its own position.
+pub struct NGramTokenizer {
+    tokenizer: Tokenizer,
+    filter: NGramFilter,
+}
+
+/// `EdgeNGramTokenizer` splits words into prefixes, every prefix gets its own position.
+pub struct EdgeNGramTokenizer {
+    tokenizer: Tokenizer,
+    filter: EdgeNGramFilter,
+}
+
+impl NGramFilter {
+    /// Creates a filter producing n-grams from `min_gram` to `max_gram` characters long.
+    /// `min_gram` is at least 1 and `max_gram` at least `min_gram`, so every token of
+    /// `min_gram` characters produces n-grams.
+    pub fn new(min_gram: usize, max_gram: usize) -> Self {
+        let (min_gram, max_gram) = gram_range(min_gram, max_gram);
+        NGramFilter {
+            min_gram,
+            max_gram,
+            preserve_original: false,
+        }
+    }
+}
+
+impl TokenFilter for NGramFilter {
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let mut res = Vec::new();
+        for token in tokens {
+            let bounds = char_bounds(&token.text);
+            let chars = bounds.len() - 1;
+            for start in 0..chars {
+                for len in self.min_gram.max(1)..=self.max_gram.min(chars - start) {
+                    let text = &token.text[bounds[start]..bounds[start + len]];
+                    if !self.preserve_original || text.len() < token.text.len() {
+                        res.push(gram(text, &token));
+                    }
+                }
+            }
+            if self.preserve_original {
+                res.push(token);
+            }
+        }
+        res
+    }
+}
+
+impl EdgeNGramFilter {
+    /// Creates a filter producing prefixes from `min_gram` to `max_gram` characters long.
+    /// `min_gram` is at least 1 and `max_gram` at least `min_gram`.
+    pub fn new(min_gram: usize, max_gram: usize) -> Self {
+        let (min_gram, max_gram) = gram_range(min_gram, max_gram);
+        EdgeNGramFilter {
+            min_gram,
+            max_gram,
+            preserve_original: false,
+        }
+    }
+}
+
+impl TokenFilter for EdgeNGramFilter {
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let mut res = Vec::new();
+        for token in tokens {
+            let bounds = char_bounds(&token.text);
+            let chars = bounds.len() - 1;
+            let prefixes = bounds.iter().enumerate().skip(self.min_gram.max(1));
+            for (len, &end) in prefixes.take_while(|&(len, _)| len <= self.max_gram) {
+                if !self.preserve_original || len < chars {
+                    res.push(gram(&token.text[..end], &token));
+                }
+            }
+            if self.preserve_original {
+                res.push(token);
+            }
+        }
+        res
+    }
+}
+
+impl ShingleFilter {
+    /// Creates a filter producing shingles from `min_shingle_size` to `max_shingle_size`
+    /// tokens long along with unigrams.
+    /// `min_shingle_size` is at least 2 and `max_shingle_size` at least `min_shingle_size`.
+    pub fn new(min_shingle_size: usize, max_shingle_size: usize) -> Self {
+        let min_shingle_size = min_shingle_size.max(2);
+        let max_shingle_size = max_shingle_size.max(min_shingle_size);
+        ShingleFilter {
+            min_shingle_size,
+            max_shingle_size,
+            output_unigrams: true,
+            filler_token: None,
+        }
+    }
+}
+
+impl Default for ShingleFilter {
+    fn default() -> Self {
+        Self::new(2, 2)
+    }
+}
+
+impl TokenFilter for ShingleFilter {
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let Some(first) = tokens.iter().map(|t| t.position).min() else {
+            return tokens;
+        };
+        // Positions before the first token are removed tokens, e.g. a leading stop word.
+        let first = match self.filler_token {
+            Some(_) => first.saturating_sub(self.max_shingle_size.saturating_sub(1)),
+            None => first,
+        };
+        let last = tokens.iter().map(|t| t.position).max().unwrap_or(first);
+        let mut slots: Vec<Option<&Token>> = vec![None; last - first + 1];
+        for token in &tokens {
+            let slot = &mut slots[token.position - first];
+            if slot.is_none() {
+                *slot = Some(token);
+            }
+        }
+
+        let mut shingles: Vec<Vec<Token>> = vec![Vec::new(); slots.len()];
+        for (start, slot) in slots.iter().enumerate() {
+            let (mut text, mut offset) = match (slot, &self.filler_token) {
+                (Some(head), _) => (head.text.clone(), Some(head.offset.clone())),
+                (None, Some(filler)) => (filler.clone(), None),
+                (None, None) => continue,
+            };
+            for size in 2..=self.max_shingle_size {
+                let Some(next) = slots.get(start + size - 1) else {
+                    break;
+                };
+                match (next, &self.filler_token) {
+                    (Some(next), _) => {
+                        text = format!("{text} {}", next.text);
+                        let start = offset.map_or(next.offset.start, |o| o.start);
+                        offset = Some(start..next.offset.end);
+                    }
+                    (None, Some(filler)) => text = format!("{text} {filler}"),
+                    (None, None) => break,
+                }
+                // Shingles made only of filler tokens have no offset and are skipped.
+                if let Some(offset) = offset.clone().filter(|_| size >= self.min_shingle_size) {
+                    shingles[start].push(Token {
+                        text: text.clone(),
+                        position: first + start,
+                        offset,
+                    });
+                }
+            }
+        }
+
+        let mut res = Vec::with_capacity(tokens.len() * 2);
+        let mut tokens = tokens.into_iter().peekable();
+        for (slot, shingles) in shingles.into_iter().enumerate() {
+            while let Some(token) = tokens.next_if(|t| t.position <= first + slot) {
+                if self.output_unigrams {
+                    res.push(token);
+                }
+            }
+            res.extend(shingles);
+        }
+        res
+    }
+}
+
+impl NGramTokenizer {
+    /// Creates a tokenizer producing n-grams from `min_gram` to `max_gram` characters long.
+    pub fn new(min_gram: usize, max_gram: usize) -> Self {
+        NGramTokenizer {
+            tokenizer: Tokenizer::new(),
+            filter: NGramFilter::new(min_gram, max_gram),
+        }
+    }
+}
+
+impl Tokenize for NGramTokenizer {
+    fn tokens(&self, text: &str) -> Vec<Token> {
+        renumber(self.filter.filter(self.tokenizer.tokens(text)))
+    }
+}
+
+impl EdgeNGramTokenizer {
+    /// Creates a tokenizer producing prefixes from `min_gram` to `max_gram` characters long.
+    pub fn new(min_gram: usize, max_gram: usize) -> Self {
+        EdgeNGramTokenizer {
+            tokenizer: Tokenizer::new(),
+            filter: EdgeNGramFilter::new(min_gram, max_gram),
+        }
+    }
+}
+
+impl Tokenize for EdgeNGramTokenizer {
+    fn tokens(&self, text: &str) -> Vec<Token> {
+        renumber(self.filter.filter(self.tokenizer.tokens(text)))
+    }
+}
+
+/// Clamps an n-gram length range to at least 1 character and `max` to at least `min`.
+fn gram_range(min: usize, max: usize) -> (usize, usize) {
+    let min = min.max(1);
+    (min, max.max(min))
+}
+
+/// Byte offsets of all characters of a text followed by the text length.
+fn char_bounds(text: &str) -> Vec<usize> {
+    text.char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(text.len()))
+        .collect()
+}
+
+fn gram(text: &str, token: &Token) -> Token {
+    Token {
+        text: text.to_string(),
+        position: token.position,
+        offset: token.offset.clone(),
+    }
+}
+
+/// Gives every token its own position.
+fn renumber(tokens: Vec<Token>) -> Vec<Token> {
+    tokens
+        .into_iter()
+        .enumerate()
+        .map(|(position, t)| Token { position, ..t })
+        .collect()
+}
+
+#[cfg(test)]
+mod ngram_tests {
+    use crate::analyzer::Analyzer;
+    use crate::filters::TokenFilter;
+    use crate::filters::{Language, LowercaseFilter, StopWordsFilter};
+    use crate::index::{Document, InvertedIndex};
+    use crate::ngram::{
+        EdgeNGramFilter, EdgeNGramTokenizer, NGramFilter, NGramTokenizer, ShingleFilter,
+    };
+    use crate::tokenizer::{Token, Tokenize, Tokenizer};
+
+    fn texts(tokens: Vec<Token>) -> Vec<String> {
+        tokens.into_iter().map(|t| t.text).collect()
+    }
+
+    #[test]
+    fn test_ngram_filter() {
+        let filter = NGramFilter::new(2, 3);
+        let res = filter.filter(Tokenizer::new().tokens("fox"));
+        assert_eq!(texts(res), ["fo", "fox", "ox"], "n-gram filter failed");
+    }
+
+    #[test]
+    fn test_ngram_filter_multibyte() {
+        let filter = NGramFilter::new(2, 2);
+        let res = filter.filter(Tokenizer::new().tokens("café"));
+        assert_eq!(texts(res), ["ca", "af", "fé"], "n-gram filter split a char");
+    }
+
+    #[test]
+    fn test_ngram_filter_preserve_original() {
+        let filter = NGramFilter {
+            min_gram: 3,
+            max_gram: 3,
+            preserve_original: true,
+        };
+        let res = filter.filter(Tokenizer::new().tokens("a fox"));
+        assert_eq!(texts(res), ["a", "fox"], "original token is not preserved");
+    }
+
+    #[test]
+    fn test_ngram_invalid_range() {
+        let tokens = Tokenizer::new().tokens("fox");
+        let res = NGramFilter::new(3, 2).filter(tokens.clone());
+        assert_eq!(texts(res), ["fox"], "inverted n-gram range failed");
+        let res = EdgeNGramFilter::new(0, 0).filter(tokens);
+        assert_eq!(texts(res), ["f"], "empty edge n-gram range failed");
+        let filter = ShingleFilter::new(3, 1);
+        let res = (filter.min_shingle_size, filter.max_shingle_size);
+        assert_eq!(res, (3, 3), "inverted shingle range failed");
+    }
+
+    #[test]
+    fn test_edge_ngram_filter() {
+        let filter = EdgeNGramFilter::new(1, 3);
+        let tokens = filter.filter(Tokenizer::new().tokens("quick fox"));
+        let res: Vec<(String, usize)> = tokens.into_iter().map(|t| (t.text, t.position)).collect();
+        let expected = [
+            ("q", 0),
+            ("qu", 0),
+            ("qui", 0),
+            ("f", 1),
+            ("fo", 1),
+            ("fox", 1),
+        ]
+        .map(|(text, position)| (text.to_string(), position));
+        assert_eq!(res, expected, "edge n-gram filter failed");
+    }
+
+    #[test]
+    fn test_ngram_tokenizer() {
+        let tokenizer = NGramTokenizer::new(3, 3);
+        let res: Vec<usize> = tokenizer
+            .tokens("brown fox")
+            .into_iter()
+            .map(|t| t.position)
+            .collect();
+        assert_eq!(res, [0, 1, 2, 3], "n-gram tokenizer positions failed");
+    }
+
+    #[test]
+    fn test_edge_ngram_tokenizer() {
+        let tokenizer = EdgeNGramTokenizer::new(2, 10);
+        let res = tokenizer.tokens("Hello");
+        assert_eq!(
+            texts(res),
+            ["He", "Hel", "Hell", "Hello"],
+            "edge n-gram tokenizer failed"
+        );
+    }
+
+    #[test]
+    fn test_shingle_filter() {
+        let filter = ShingleFilter::new(2, 3);
+        let tokens = filter.filter(Tokenizer::new().tokens("quick brown fox"));
+        let res: Vec<(String, usize)> = tokens.into_iter().map(|t| (t.text, t.position)).collect();
+        let expected = [
+            ("quick", 0),
+            ("quick brown", 0),
+            ("quick brown fox", 0),
+            ("brown", 1),
+            ("brown fox", 1),
+            ("fox", 2),
+        ]
+        .map(|(text, position)| (text.to_string(), position));
+        assert_eq!(res, expected, "shingle filter failed");
+    }
+
+    #[test]
+    fn test_shingle_filter_stop_word_gaps() {
+        let tokens = StopWordsFilter::new(&Language::English)
+            .filter(Tokenizer::new().tokens("quick and the fox jumps"));
+        let filter = ShingleFilter {
+            output_unigrams: false,
+            ..ShingleFilter::default()
+        };
+        let res = texts(filter.filter(tokens.clone()));
+        assert_eq!(res, ["fox jumps"], "shingle spans removed stop words");
+
+        let filter = ShingleFilter {
+            output_unigrams: false,
+            filler_token: Some("_".to_string()),
+            ..ShingleFilter::default()
+        };
+        let res = texts(filter.filter(tokens));
+        assert_eq!(
+            res,
+            ["quick _", "_ fox", "fox jumps"],
+            "filler token failed"
+        );
+
+        let text = "the fox";
+        let tokens = StopWordsFilter::new(&Language::English).filter(Tokenizer::new().tokens(text));
+        let res = filter.filter(tokens);
+        let res: Vec<(&str, usize, &str)> = res
+            .iter()
+            .map(|t| (t.text.as_str(), t.position, &text[t.offset.clone()]))
+            .collect();
+        assert_eq!(res, [("_ fox", 0, "fox")], "leading filler token failed");
+    }
+
+    #[test]
+    fn test_shingle_offsets() {
+        let text = "quick, brown";
+        let res = ShingleFilter::default().filter(Tokenizer::new().tokens(text));
+        assert_eq!(
+            &text[res[1].offset.clone()],
+            "quick, brown",
+            "shingle offsets failed"
+        );
+    }
+
+    #[test]
+    fn test_shingle_search() {
+        let analyzer = Analyzer::custom(
+            Tokenizer::new(),
+            vec![
+                Box::new(LowercaseFilter),
+                Box::new(ShingleFilter::default()),
+            ],
+        );
+        let mut index = InvertedIndex::with_analyzer(analyzer);
+        index.add(&[
+            Document::new(1, "The quick brown fox"),
+            Document::new(2, "The brown and quick fox"),
+        ]);
+        assert_eq!(index.search("quick brown"), [1], "shingle search failed");
+    }
+}
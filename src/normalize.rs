@@ -0,0 +1,208 @@
+/// Normalization filters make equivalent spellings of a word produce the same term.
+/// `NormalizationFilter` applies Unicode normalization, so composed "é" and "e" followed
+/// by a combining acute accent become the same token.
+/// `AsciiFoldingFilter` strips diacritics from Latin letters, so "café" matches "cafe".
+/// For more details, see https://www.unicode.org/reports/tr15/
+use crate::filters::TokenFilter;
+use crate::tokenizer::Token;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Unicode normalization forms.
+/// `Nfc`, `Nfd`: canonical composition and decomposition, keep the meaning of a text
+/// `Nfkc`, `Nfkd`: compatibility composition and decomposition, also unify ligatures,
+/// full-width forms, superscripts and similar variants of characters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalizationForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+/// `NormalizationFilter` converts tokens to a Unicode normalization form.
+#[derive(Clone, Copy, Debug)]
+pub struct NormalizationFilter {
+    pub form: NormalizationForm,
+}
+
+/// `AsciiFoldingFilter` converts Latin letters with diacritics and their variants to ASCII.
+/// Letters of other scripts are kept as is.
+/// `preserve_original`: keeps the original token at the same position if it was changed
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AsciiFoldingFilter {
+    pub preserve_original: bool,
+}
+
+impl NormalizationFilter {
+    pub fn new(form: NormalizationForm) -> Self {
+        NormalizationFilter { form }
+    }
+
+    fn normalize(&self, text: &str) -> String {
+        match self.form {
+            NormalizationForm::Nfc => text.nfc().collect(),
+            NormalizationForm::Nfd => text.nfd().collect(),
+            NormalizationForm::Nfkc => text.nfkc().collect(),
+            NormalizationForm::Nfkd => text.nfkd().collect(),
+        }
+    }
+}
+
+impl TokenFilter for NormalizationFilter {
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+        tokens
+            .into_iter()
+            .map(|t| Token {
+                text: self.normalize(&t.text),
+                ..t
+            })
+            .collect()
+    }
+}
+
+impl AsciiFoldingFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Folds a text to ASCII, characters without an ASCII equivalent are kept.
+    pub fn fold(text: &str) -> String {
+        let mut res = String::with_capacity(text.len());
+        for c in text.nfc() {
+            if c.is_ascii() {
+                res.push(c);
+            } else if let Some(folded) = fold_special(c) {
+                res.push_str(folded);
+            } else {
+                let base: String = c.nfkd().filter(|&c| !is_combining_mark(c)).collect();
+                if !base.is_empty() && base.is_ascii() {
+                    res.push_str(&base);
+                } else {
+                    res.push(c);
+                }
+            }
+        }
+        res
+    }
+}
+
+impl TokenFilter for AsciiFoldingFilter {
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let mut res = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            let folded = Self::fold(&token.text);
+            if folded == token.text {
+                res.push(token);
+                continue;
+            }
+            let folded = Token {
+                text: folded,
+                ..token.clone()
+            };
+            if self.preserve_original {
+                res.push(token);
+            }
+            res.push(folded);
+        }
+        res
+    }
+}
+
+/// Latin letters which have no canonical or compatibility decomposition to ASCII.
+fn fold_special(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'ß' => "ss",
+        'ẞ' => "SS",
+        'æ' => "ae",
+        'Æ' => "AE",
+        'œ' => "oe",
+        'Œ' => "OE",
+        'ø' => "o",
+        'Ø' => "O",
+        'đ' | 'ð' => "d",
+        'Đ' | 'Ð' => "D",
+        'ł' => "l",
+        'Ł' => "L",
+        'þ' => "th",
+        'Þ' => "TH",
+        'ħ' => "h",
+        'Ħ' => "H",
+        'ı' => "i",
+        'ŋ' => "n",
+        'Ŋ' => "N",
+        'ŧ' => "t",
+        'Ŧ' => "T",
+        'ſ' => "s",
+        '‘' | '’' | '‚' | '′' => "'",
+        '“' | '”' | '„' | '″' => "\"",
+        '–' | '—' | '‐' | '‑' => "-",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+#[cfg(test)]
+mod normalize_tests {
+    use crate::analyzer::Analyzer;
+    use crate::filters::{LowercaseFilter, TokenFilter};
+    use crate::normalize::{AsciiFoldingFilter, NormalizationFilter, NormalizationForm};
+    use crate::tokenizer::{Token, Tokenize, Tokenizer};
+
+    fn texts(tokens: Vec<Token>) -> Vec<String> {
+        tokens.into_iter().map(|t| t.text).collect()
+    }
+
+    #[test]
+    fn test_nfc() {
+        let filter = NormalizationFilter::new(NormalizationForm::Nfc);
+        let res = texts(filter.filter(Tokenizer::new().tokens("cafe\u{301}")));
+        assert_eq!(res, ["caf\u{e9}"], "nfc normalization failed");
+    }
+
+    #[test]
+    fn test_nfkc() {
+        let filter = NormalizationFilter::new(NormalizationForm::Nfkc);
+        let res = texts(filter.filter(Tokenizer::new().tokens("ﬁnal Ｒｕｓｔ")));
+        assert_eq!(res, ["final", "Rust"], "nfkc normalization failed");
+    }
+
+    #[test]
+    fn test_ascii_folding() {
+        let filter = AsciiFoldingFilter::new();
+        let tokens = Tokenizer::new().tokens("Crème brûlée à Łódź, straße привет");
+        let res = texts(filter.filter(tokens));
+        let expected = ["Creme", "brulee", "a", "Lodz", "strasse", "привет"];
+        assert_eq!(res, expected, "ascii folding failed");
+    }
+
+    #[test]
+    fn test_ascii_folding_preserve_original() {
+        let filter = AsciiFoldingFilter {
+            preserve_original: true,
+        };
+        let tokens = filter.filter(Tokenizer::new().tokens("café au"));
+        let res: Vec<(String, usize)> = tokens.into_iter().map(|t| (t.text, t.position)).collect();
+        let expected = [("café", 0), ("cafe", 0), ("au", 1)].map(|(t, p)| (t.to_string(), p));
+        assert_eq!(res, expected, "original token is not preserved");
+    }
+
+    #[test]
+    fn test_folded_terms_match() {
+        let analyzer = Analyzer::custom(
+            Tokenizer::new(),
+            vec![
+                Box::new(LowercaseFilter),
+                Box::new(AsciiFoldingFilter::new()),
+            ],
+        );
+        let res: Vec<Vec<String>> = ["café", "cafe", "CAFÉ", "CAFE\u{301}"]
+            .iter()
+            .map(|text| analyzer.analyze(text))
+            .collect();
+        assert!(
+            res.iter().all(|terms| terms == &["cafe"]),
+            "folded terms differ: {res:?}"
+        );
+    }
+}
@@ -0,0 +1,147 @@
+/// Percolation inverts search: queries are stored, and every incoming document is matched
+/// against them without being indexed, e.g. to send alerts for new documents.
+/// Every stored query is indexed by one of its terms, so only queries sharing that term
+/// with a document are checked, which scales to many stored queries.
+/// The longest term is picked as it tends to be the rarest one.
+use crate::analyzer::Analyzer;
+use crate::filters::Language;
+use crate::index::Document;
+use std::collections::{HashMap, HashSet};
+
+/// `Percolator` keeps queries with ids and finds queries matching a document.
+/// A query matches a document containing all its terms, like `InvertedIndex::search`.
+/// Queries and documents are analyzed with the same analyzer, which should be the
+/// analyzer of the index the queries are written for.
+pub struct Percolator {
+    analyzer: Analyzer,
+    queries: HashMap<u64, Vec<String>>,
+    idx: HashMap<String, HashSet<u64>>,
+}
+
+/// `Default` assume that a text will be in English.
+impl Default for Percolator {
+    fn default() -> Self {
+        Self::new(Language::English)
+    }
+}
+
+impl Percolator {
+    /// Creates empty percolator with custom language.
+    pub fn new(language: Language) -> Self {
+        Self::with_analyzer(Analyzer::new(language))
+    }
+
+    /// Creates empty percolator with custom analyzer.
+    pub fn with_analyzer(analyzer: Analyzer) -> Self {
+        Percolator {
+            analyzer,
+            queries: HashMap::new(),
+            idx: HashMap::new(),
+        }
+    }
+
+    /// Stores a query, a query with the same id is replaced.
+    /// Queries without terms, e.g. of stop words only, never match.
+    pub fn register(&mut self, id: u64, query: &str) {
+        self.remove(id);
+        let terms = self.analyzer.analyze(query);
+        if let Some(term) = key_term(&terms) {
+            self.idx.entry(term.clone()).or_default().insert(id);
+        }
+        self.queries.insert(id, terms);
+    }
+
+    /// Removes a stored query.
+    pub fn remove(&mut self, id: u64) {
+        let Some(terms) = self.queries.remove(&id) else {
+            return;
+        };
+        let Some(term) = key_term(&terms) else {
+            return;
+        };
+        if let Some(ids) = self.idx.get_mut(term) {
+            ids.remove(&id);
+            if ids.is_empty() {
+                self.idx.remove(term);
+            }
+        }
+    }
+
+    /// Returns ids of stored queries matching a document, ordered by query id.
+    /// Terms of all fields of the document are matched.
+    pub fn percolate(&self, doc: &Document) -> Vec<u64> {
+        let terms: HashSet<String> = doc
+            .field_texts()
+            .flat_map(|(_, text)| self.analyzer.analyze(text))
+            .collect();
+        let mut res: Vec<u64> = terms
+            .iter()
+            .filter_map(|term| self.idx.get(term))
+            .flatten()
+            .copied()
+            .filter(|id| self.queries[id].iter().all(|term| terms.contains(term)))
+            .collect();
+        res.sort_unstable();
+        res
+    }
+}
+
+/// Returns the term a query is indexed by.
+fn key_term(terms: &[String]) -> Option<&String> {
+    terms
+        .iter()
+        .max_by(|a, b| a.len().cmp(&b.len()).then(b.cmp(a)))
+}
+
+#[cfg(test)]
+mod percolator_tests {
+    use crate::index::Document;
+    use crate::percolator::Percolator;
+
+    fn doc(text: &str) -> Document {
+        Document::new(1, text.to_string())
+    }
+
+    #[test]
+    fn test_percolate() {
+        let mut percolator = Percolator::default();
+        percolator.register(1, "brown fox");
+        percolator.register(2, "lazy dogs");
+        percolator.register(3, "fox in summer");
+        percolator.register(4, "the");
+
+        let res = percolator.percolate(&doc("The quick brown foxes jumped over the lazy dog"));
+        assert_eq!(res, [1, 2], "percolation failed");
+        let res = percolator.percolate(&doc("Foxes sleep in summer"));
+        assert_eq!(res, [3], "percolation failed");
+    }
+
+    #[test]
+    fn test_register_and_remove() {
+        let mut percolator = Percolator::default();
+        percolator.register(1, "brown fox");
+        percolator.register(2, "brown fox");
+        percolator.remove(2);
+        percolator.register(1, "lazy dog");
+
+        let res = percolator.percolate(&doc("The quick brown fox"));
+        assert!(res.is_empty(), "removed or replaced query matched");
+        let res = percolator.percolate(&doc("The lazy dog"));
+        assert_eq!(res, [1], "replaced query failed");
+        assert!(
+            !percolator.idx.contains_key("brown"),
+            "empty postings are kept"
+        );
+    }
+
+    #[test]
+    fn test_percolate_many_queries() {
+        let mut percolator = Percolator::default();
+        for id in 0..20_000 {
+            percolator.register(id, &format!("alert{} server", id % 1000));
+        }
+        let res = percolator.percolate(&doc("alert42 raised on server"));
+        let expected: Vec<u64> = (0..20).map(|i| i * 1000 + 42).collect();
+        assert_eq!(res, expected, "percolation of many queries failed");
+    }
+}
@@ -0,0 +1,197 @@
+/// Query text may boost words with "^" and a positive number, e.g. in "fox^3 dog" matching
+/// "fox" counts three times as much as matching "dog" in scores.
+/// Boosts change scores only, documents match the query as if there were no boosts.
+/// Ranking changes when matched documents contain different terms, see
+/// `InvertedIndex::search_scored_any`.
+/// Fields of documents are boosted separately, see `Query::with_field_boosts`.
+/// A field boost multiplies the term frequency of a term found in the field, and BM25
+/// saturates term frequencies, so with "title^2" a term in the "title" field raises
+/// the score more than in another field, but less than twice as much.
+use crate::analyzer::Analyzer;
+use std::ops::Range;
+
+/// `Query` is a query text without boosts and boosts of its words and fields.
+/// `boosts`: are byte ranges of boosted words in the text with their boosts
+/// `field_boosts`: are names of boosted fields with their boosts
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    text: String,
+    boosts: Vec<(Range<usize>, f64)>,
+    field_boosts: Vec<(String, f64)>,
+}
+
+impl Query {
+    /// Parses boosts of whitespace separated words, invalid boosts are kept in the text.
+    pub fn parse(text: &str) -> Self {
+        let mut res = Query {
+            text: String::with_capacity(text.len()),
+            boosts: Vec::new(),
+            field_boosts: Vec::new(),
+        };
+        let mut end = 0;
+        for word in words(text) {
+            res.text.push_str(&text[end..word.start]);
+            end = word.end;
+            let word = &text[word];
+            match split_boost(word) {
+                Some((word, boost)) => {
+                    let start = res.text.len();
+                    res.text.push_str(word);
+                    res.boosts.push((start..res.text.len(), boost));
+                }
+                None => res.text.push_str(word),
+            }
+        }
+        res.text.push_str(&text[end..]);
+        res
+    }
+
+    /// Sets boosts of fields from specs like "title^2", a field without a boost has a
+    /// boost of 1, so ["title"] is the same as no boosts.
+    /// Fields not listed keep a boost of 1, invalid boosts are ignored.
+    pub fn with_field_boosts(mut self, fields: &[&str]) -> Self {
+        self.field_boosts = fields
+            .iter()
+            .filter_map(|spec| split_boost(spec))
+            .map(|(field, boost)| (field.to_string(), boost))
+            .collect();
+        self
+    }
+
+    /// Returns the boost of a field, 1 for a field without a boost.
+    pub fn field_boost(&self, field: &str) -> f64 {
+        self.field_boosts
+            .iter()
+            .find(|(name, _)| name == field)
+            .map_or(1.0, |(_, boost)| *boost)
+    }
+
+    /// Returns the query text without boosts.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns distinct analyzed terms with their boosts, terms of words without a boost
+    /// have a boost of 1.
+    /// A term found several times has the highest of its boosts.
+    pub fn terms(&self, analyzer: &Analyzer) -> Vec<(String, f64)> {
+        let mut res: Vec<(String, f64)> = Vec::new();
+        for token in analyzer.token_stream(&self.text) {
+            let boost = self
+                .boosts
+                .iter()
+                .find(|(range, _)| range.contains(&token.offset.start))
+                .map_or(1.0, |(_, boost)| *boost);
+            match res.iter_mut().find(|(term, _)| *term == token.text) {
+                Some((_, max)) => *max = max.max(boost),
+                None => res.push((token.text, boost)),
+            }
+        }
+        res
+    }
+}
+
+/// Splits "word^2" into the word and its boost, `None` for a word without a valid boost.
+fn split_boost(word: &str) -> Option<(&str, f64)> {
+    let (word, boost) = word.rsplit_once('^')?;
+    let boost: f64 = boost.parse().ok()?;
+    (!word.is_empty() && boost.is_finite() && boost > 0.0).then_some((word, boost))
+}
+
+/// Returns byte ranges of whitespace separated words.
+fn words(text: &str) -> Vec<Range<usize>> {
+    let mut res = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                res.push(s..i);
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    res.extend(start.map(|s| s..text.len()));
+    res
+}
+
+#[cfg(test)]
+mod query_tests {
+    use crate::analyzer::Analyzer;
+    use crate::filters::Language;
+    use crate::index::{Document, InvertedIndex};
+    use crate::query::Query;
+    use crate::test_support::docs;
+
+    #[test]
+    fn test_parse_boosts() {
+        let query = Query::parse("quick foxes^3  dog^0.5 cat^ bird^x");
+        assert_eq!(
+            query.text(),
+            "quick foxes  dog cat^ bird^x",
+            "parsing boosts failed"
+        );
+        let terms = query.terms(&Analyzer::new(Language::English));
+        let expected = [
+            ("quick", 1.0),
+            ("fox", 3.0),
+            ("dog", 0.5),
+            ("cat", 1.0),
+            ("bird", 1.0),
+            ("x", 1.0),
+        ]
+        .map(|(term, boost)| (term.to_string(), boost));
+        assert_eq!(terms, expected, "term boosts failed");
+    }
+
+    #[test]
+    fn test_boosted_search() {
+        let mut idx = InvertedIndex::default();
+        idx.add(&docs(&[
+            "The brown fox",
+            "The lazy dog",
+            "The fox and the dog",
+        ]));
+
+        assert_eq!(idx.search("fox^3 dog"), [3], "boost changed matches");
+        let ids = |text| -> Vec<u64> {
+            let res = idx.search_scored_any(text);
+            res.into_iter().map(|(id, _)| id).collect()
+        };
+        assert_eq!(ids("fox^3 dog"), [3, 1, 2], "term boost failed");
+        assert_eq!(ids("fox dog^3"), [3, 2, 1], "term boost failed");
+    }
+
+    #[test]
+    fn test_field_boosts() {
+        let query = Query::parse("fox").with_field_boosts(&["title^2", "body^x", "tags"]);
+        assert_eq!(
+            query.field_boost("title"),
+            2.0,
+            "parsing field boosts failed"
+        );
+        assert_eq!(query.field_boost("body"), 1.0, "invalid field boost kept");
+        assert_eq!(query.field_boost("text"), 1.0, "default field boost failed");
+
+        let mut idx = InvertedIndex::default();
+        idx.add(&[
+            Document::new(1, "The quick brown fox").with_field("title", "Dogs"),
+            Document::new(2, "The quick brown dog").with_field("title", "Foxes"),
+        ]);
+        let ids = |query: &Query| -> Vec<u64> {
+            let res = idx.search_query(query);
+            res.into_iter().map(|(id, _)| id).collect()
+        };
+        assert_eq!(ids(&Query::parse("fox")), [1, 2], "field search failed");
+        let query = Query::parse("fox").with_field_boosts(&["title^2"]);
+        assert_eq!(ids(&query), [2, 1], "field boost failed");
+        let query = Query::parse("fox").with_field_boosts(&["text^3"]);
+        assert_eq!(ids(&query), [1, 2], "text field boost failed");
+
+        let score = |query: &Query| idx.search_query(query)[0].1;
+        let boosted = score(&Query::parse("fox").with_field_boosts(&["title^2"]));
+        let ratio = boosted / score(&Query::parse("fox"));
+        assert!(ratio > 1.0 && ratio < 2.0, "field boost is not saturated");
+    }
+}
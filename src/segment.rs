@@ -0,0 +1,156 @@
+/// This is the place where index segments are implemented.
+/// A segment is an immutable part of an index created by a commit or a merge.
+/// Deletions never modify a segment, they are tracked next to it instead.
+use crate::merge_policy::SegmentInfo;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// `Segment` holds postings for documents added between two commits.
+#[derive(Default)]
+pub(crate) struct Segment {
+    idx: HashMap<String, HashSet<u64>>,
+    docs: HashSet<u64>,
+}
+
+/// `SegmentEntry` is a segment published in an index together with its deleted documents.
+#[derive(Clone)]
+pub(crate) struct SegmentEntry {
+    pub(crate) id: u64,
+    segment: Arc<Segment>,
+    deleted: Arc<HashSet<u64>>,
+}
+
+impl Segment {
+    /// Adds document tokens to a segment which is not published yet.
+    pub(crate) fn add<I>(&mut self, id: u64, tokens: I)
+    where
+        I: Iterator<Item = String>,
+    {
+        for token in tokens {
+            self.idx.entry(token).or_default().insert(id);
+        }
+        self.docs.insert(id);
+    }
+
+    /// Removes document from a segment which is not published yet.
+    pub(crate) fn remove(&mut self, id: u64) {
+        if self.docs.remove(&id) {
+            self.idx.retain(|_, ids| {
+                ids.remove(&id);
+                !ids.is_empty()
+            });
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    /// Merges segments into a new one leaving deleted documents out.
+    pub(crate) fn merge(entries: &[SegmentEntry]) -> Segment {
+        let mut merged = Segment::default();
+        for entry in entries {
+            for (token, ids) in entry.segment.idx.iter() {
+                let live = ids.difference(&entry.deleted).copied();
+                merged.idx.entry(token.clone()).or_default().extend(live);
+            }
+            let live = entry.segment.docs.difference(&entry.deleted).copied();
+            merged.docs.extend(live);
+        }
+        merged.idx.retain(|_, ids| !ids.is_empty());
+        merged
+    }
+}
+
+impl SegmentEntry {
+    pub(crate) fn new(id: u64, segment: Segment) -> Self {
+        SegmentEntry {
+            id,
+            segment: Arc::new(segment),
+            deleted: Arc::new(HashSet::new()),
+        }
+    }
+
+    /// Publishes a merged segment.
+    /// `sources` are the current entries of merged segments,
+    /// documents deleted from them while merging stay deleted.
+    pub(crate) fn merged(id: u64, segment: Segment, sources: &[&SegmentEntry]) -> Self {
+        let deleted = sources
+            .iter()
+            .flat_map(|entry| entry.deleted.iter())
+            .filter(|id| segment.docs.contains(id))
+            .copied()
+            .collect();
+        SegmentEntry {
+            id,
+            segment: Arc::new(segment),
+            deleted: Arc::new(deleted),
+        }
+    }
+
+    /// Marks documents stored in this segment as deleted.
+    pub(crate) fn delete(&mut self, ids: &HashSet<u64>) {
+        let found: Vec<u64> = ids
+            .iter()
+            .filter(|id| self.segment.docs.contains(id) && !self.deleted.contains(id))
+            .copied()
+            .collect();
+        if !found.is_empty() {
+            Arc::make_mut(&mut self.deleted).extend(found);
+        }
+    }
+
+    /// Ids of live documents containing the token.
+    pub(crate) fn postings(&self, token: &str) -> impl Iterator<Item = u64> + '_ {
+        self.segment
+            .idx
+            .get(token)
+            .into_iter()
+            .flat_map(|ids| ids.difference(&self.deleted))
+            .copied()
+    }
+
+    pub(crate) fn info(&self) -> SegmentInfo {
+        SegmentInfo {
+            num_docs: self.segment.docs.len(),
+            num_deleted: self.deleted.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod segment_tests {
+    use crate::segment::{Segment, SegmentEntry};
+    use std::collections::HashSet;
+
+    fn entry(id: u64, docs: &[(u64, &str)]) -> SegmentEntry {
+        let mut segment = Segment::default();
+        for &(doc, text) in docs {
+            segment.add(doc, text.split(' ').map(str::to_string));
+        }
+        SegmentEntry::new(id, segment)
+    }
+
+    #[test]
+    fn merge_test() {
+        let mut first = entry(0, &[(1, "quick fox"), (2, "lazy dog")]);
+        let second = entry(1, &[(3, "quick dog")]);
+        first.delete(&HashSet::from([2]));
+        let merged = Segment::merge(&[first, second]);
+        assert!(
+            !merged.idx.contains_key("lazy"),
+            "deleted doc terms survived"
+        );
+        assert_eq!(merged.docs, HashSet::from([1, 3]), "merge failed");
+    }
+
+    #[test]
+    fn merged_keeps_new_deletes_test() {
+        let mut source = entry(0, &[(1, "quick fox"), (2, "lazy dog")]);
+        let merged = Segment::merge(&[source.clone()]);
+        source.delete(&HashSet::from([2]));
+        let entry = SegmentEntry::merged(1, merged, &[&source]);
+        let ids: Vec<u64> = entry.postings("dog").collect();
+        assert!(ids.is_empty(), "delete made during merge is lost");
+    }
+}
@@ -0,0 +1,272 @@
+/// Special tokenizer and filter for text from the web and source code.
+/// `SpecialTokenizer` keeps URLs, emails, @mentions and #hashtags as single tokens,
+/// which are split into fragments by Unicode Text Segmentation.
+/// `IdentifierSplitFilter` splits camelCase and snake_case identifiers into subwords.
+use crate::filters::TokenFilter;
+use crate::tokenizer::{Token, Tokenize};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// `SpecialTokenizer` recognizes URLs, emails, @mentions and #hashtags,
+/// other text is split into words like `Tokenizer` does.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpecialTokenizer;
+
+/// `IdentifierSplitFilter` splits identifiers into subwords on underscores and case changes,
+/// e.g. "parseHTTPRequest" produces "parse", "HTTP" and "Request".
+/// Subwords share the position of the identifier. It must be applied before lowercasing.
+/// `preserve_original`: keeps the identifier itself before its subwords
+#[derive(Clone, Copy, Debug)]
+pub struct IdentifierSplitFilter {
+    pub preserve_original: bool,
+}
+
+impl SpecialTokenizer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tokenize for SpecialTokenizer {
+    fn tokens(&self, text: &str) -> Vec<Token> {
+        let mut offsets: Vec<Range<usize>> = Vec::new();
+        for (start, chunk) in text.split_word_bound_indices() {
+            if chunk.trim().is_empty() {
+                continue;
+            }
+            // Skips the rest of a special token.
+            if offsets.last().is_some_and(|last| last.end > start) {
+                continue;
+            }
+            // Special tokens never contain whitespace.
+            let rest = &text[start..];
+            let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let special = trim_punctuation(&rest[..len]);
+            if start_of_word(text, start) && is_special(special) {
+                offsets.push(start..start + special.len());
+            } else if chunk.chars().any(char::is_alphanumeric) {
+                offsets.push(start..start + chunk.len());
+            }
+        }
+
+        offsets
+            .into_iter()
+            .enumerate()
+            .map(|(position, offset)| Token {
+                text: text[offset.clone()].to_string(),
+                position,
+                offset,
+            })
+            .collect()
+    }
+}
+
+impl Default for IdentifierSplitFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdentifierSplitFilter {
+    /// Creates a filter which keeps identifiers along with their subwords.
+    pub fn new() -> Self {
+        IdentifierSplitFilter {
+            preserve_original: true,
+        }
+    }
+
+    /// Splits an identifier into byte ranges of its subwords.
+    pub fn split(text: &str) -> Vec<Range<usize>> {
+        let mut res = Vec::new();
+        let mut offset = 0;
+        for part in text.split('_') {
+            let chars: Vec<(usize, char)> = part.char_indices().collect();
+            let mut start = 0;
+            for i in 1..chars.len() {
+                let (prev, cur) = (chars[i - 1].1, chars[i].1);
+                let next_lower = chars.get(i + 1).is_some_and(|&(_, c)| c.is_lowercase());
+                let camel = (prev.is_lowercase() || prev.is_numeric()) && cur.is_uppercase();
+                let acronym = prev.is_uppercase() && cur.is_uppercase() && next_lower;
+                if camel || acronym {
+                    res.push(offset + start..offset + chars[i].0);
+                    start = chars[i].0;
+                }
+            }
+            if start < part.len() {
+                res.push(offset + start..offset + part.len());
+            }
+            offset += part.len() + 1;
+        }
+        res
+    }
+}
+
+impl TokenFilter for IdentifierSplitFilter {
+    fn filter(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let mut res = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            if !token.text.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                res.push(token);
+                continue;
+            }
+            let parts = Self::split(&token.text);
+            if parts.len() < 2 {
+                res.push(token);
+                continue;
+            }
+            let subwords: Vec<Token> = parts.into_iter().map(|r| token.subword(r)).collect();
+            if self.preserve_original {
+                res.push(token);
+            }
+            res.extend(subwords);
+        }
+        res
+    }
+}
+
+/// Removes trailing punctuation, e.g. a full stop after a URL, keeps trailing "/".
+fn trim_punctuation(chunk: &str) -> &str {
+    chunk.trim_end_matches(|c: char| !(c.is_alphanumeric() || c == '/'))
+}
+
+/// Returns true if a special token can start at `start`, not in the middle of a word.
+fn start_of_word(text: &str, start: usize) -> bool {
+    !text[..start]
+        .chars()
+        .next_back()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn is_special(text: &str) -> bool {
+    is_url(text) || is_email(text) || is_tag(text, '@') || is_tag(text, '#')
+}
+
+fn is_url(text: &str) -> bool {
+    let lower = text.to_ascii_lowercase();
+    ["http://", "https://", "ftp://", "www."]
+        .iter()
+        .any(|scheme| lower.len() > scheme.len() && lower.starts_with(scheme))
+}
+
+fn is_email(text: &str) -> bool {
+    let Some((local, domain)) = text.split_once('@') else {
+        return false;
+    };
+    let local_ok = !local.is_empty()
+        && local
+            .chars()
+            .all(|c| c.is_alphanumeric() || "._%+-".contains(c));
+    let labels: Vec<&str> = domain.split('.').collect();
+    let domain_ok = labels.len() > 1
+        && labels
+            .iter()
+            .all(|l| !l.is_empty() && l.chars().all(|c| c.is_alphanumeric() || c == '-'));
+    let tld_ok = labels
+        .last()
+        .is_some_and(|tld| tld.chars().count() > 1 && tld.chars().all(char::is_alphabetic));
+    local_ok && domain_ok && tld_ok
+}
+
+/// Returns true for @mentions and #hashtags, a hashtag must contain a letter.
+fn is_tag(text: &str, prefix: char) -> bool {
+    let Some(name) = text.strip_prefix(prefix) else {
+        return false;
+    };
+    !name.is_empty()
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && (prefix == '@' || name.chars().any(char::is_alphabetic))
+}
+
+#[cfg(test)]
+mod special_tests {
+    use crate::analyzer::Analyzer;
+    use crate::filters::{LowercaseFilter, TokenFilter};
+    use crate::special::{IdentifierSplitFilter, SpecialTokenizer};
+    use crate::tokenizer::{Token, Tokenize};
+
+    fn texts(tokens: Vec<Token>) -> Vec<String> {
+        tokens.into_iter().map(|t| t.text).collect()
+    }
+
+    #[test]
+    fn test_urls_and_emails() {
+        let tokenizer = SpecialTokenizer::new();
+        let text = "Mail user.name@example.com or see (https://x.io/a?b=1), www.rust-lang.org.";
+        let res = texts(tokenizer.tokens(text));
+        let expected = [
+            "Mail",
+            "user.name@example.com",
+            "or",
+            "see",
+            "https://x.io/a?b=1",
+            "www.rust-lang.org",
+        ];
+        assert_eq!(res, expected, "url and email tokenization failed");
+    }
+
+    #[test]
+    fn test_mentions_and_hashtags() {
+        let tokenizer = SpecialTokenizer::new();
+        let res = tokenizer.tokens("Thanks @ferris_crab for #RustLang! Issue #42 a#b");
+        let expected = [
+            "Thanks",
+            "@ferris_crab",
+            "for",
+            "#RustLang",
+            "Issue",
+            "42",
+            "a",
+            "b",
+        ];
+        assert_eq!(texts(res.clone()), expected, "tag tokenization failed");
+        assert_eq!(res[3].offset, 24..33, "offsets failed");
+    }
+
+    #[test]
+    fn test_identifier_split() {
+        let filter = IdentifierSplitFilter::new();
+        let tokens =
+            filter.filter(SpecialTokenizer::new().tokens("parseHTTPRequest snake_case_name"));
+        let res: Vec<(String, usize)> = tokens.into_iter().map(|t| (t.text, t.position)).collect();
+        let expected = [
+            ("parseHTTPRequest", 0),
+            ("parse", 0),
+            ("HTTP", 0),
+            ("Request", 0),
+            ("snake_case_name", 1),
+            ("snake", 1),
+            ("case", 1),
+            ("name", 1),
+        ]
+        .map(|(t, p)| (t.to_string(), p));
+        assert_eq!(res, expected, "identifier split failed");
+    }
+
+    #[test]
+    fn test_identifier_split_offsets() {
+        let filter = IdentifierSplitFilter {
+            preserve_original: false,
+        };
+        let text = "call utf8Decoder";
+        let res: Vec<&str> = filter
+            .filter(SpecialTokenizer::new().tokens(text))
+            .into_iter()
+            .map(|t| &text[t.offset])
+            .collect();
+        assert_eq!(res, ["call", "utf8", "Decoder"], "subword offsets failed");
+    }
+
+    #[test]
+    fn test_analyze_identifiers() {
+        let analyzer = Analyzer::custom(
+            SpecialTokenizer::new(),
+            vec![
+                Box::new(IdentifierSplitFilter::new()),
+                Box::new(LowercaseFilter),
+            ],
+        );
+        let res = analyzer.analyze("getUserName @Bob");
+        let expected = ["getusername", "get", "user", "name", "@bob"];
+        assert_eq!(res, expected, "identifiers analyze failed");
+    }
+}
@@ -0,0 +1,237 @@
+/// Spelling correction suggests indexed terms for misspelled query terms ("did you mean").
+/// Candidates are terms of the index vocabulary within a small edit distance, so it works
+/// offline and only suggests terms which match documents.
+/// Terms are compared analyzed, e.g. stemmed, and suggested as the words they are most often
+/// indexed from, e.g. "lazy" rather than the stem "lazi".
+use crate::index::InvertedIndex;
+use std::collections::HashMap;
+
+/// `Suggestion` is an indexed term close to a query term.
+/// `word`: is the word the term is most often indexed from, it's shown to users
+/// `distance`: is a number of inserted, deleted, replaced or swapped characters of the terms
+/// `doc_freq`: is a number of documents containing the term
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    pub term: String,
+    pub word: String,
+    pub distance: usize,
+    pub doc_freq: usize,
+}
+
+impl InvertedIndex {
+    /// Suggests corrections of a word, which is analyzed like query text.
+    /// Terms missing from the index get suggestions within an edit distance of 1 for
+    /// words of 3 to 5 characters and 2 for longer words, shorter words aren't corrected.
+    /// Indexed terms only get suggestions found in more documents.
+    /// In an index with language detection only terms of the language of the word
+    /// are suggested.
+    /// Suggestions are ordered by distance, then by descending document frequency.
+    pub fn suggest(&self, word: &str) -> Vec<Suggestion> {
+        let index = self.language_index(self.detect(word));
+        let vocabulary = index.vocabulary();
+        let Some(term) = index.analyzer().analyze(word).into_iter().next() else {
+            return Vec::new();
+        };
+        index.suggestions(&vocabulary, &term)
+    }
+
+    /// Corrects every query term missing from the index with its best suggestion.
+    /// Returns the query with misspelled words replaced, `None` if nothing is corrected.
+    /// Replacements keep the case of the misspelled word, e.g. title case or all caps.
+    pub fn did_you_mean(&self, text: &str) -> Option<String> {
+        let index = self.language_index(self.detect(text));
+        let vocabulary = index.vocabulary();
+        let mut res = String::with_capacity(text.len());
+        let mut end = 0;
+        for token in index.analyzer().token_stream(text) {
+            if token.offset.start < end || vocabulary.contains_key(token.text.as_str()) {
+                continue;
+            }
+            let suggestions = index.suggestions(&vocabulary, &token.text);
+            let Some(best) = suggestions.into_iter().next() else {
+                continue;
+            };
+            res.push_str(&text[end..token.offset.start]);
+            res.push_str(&with_case(&text[token.offset.clone()], &best.word));
+            end = token.offset.end;
+        }
+        if end == 0 {
+            return None;
+        }
+        res.push_str(&text[end..]);
+        Some(res)
+    }
+
+    fn suggestions(&self, vocabulary: &HashMap<&str, usize>, term: &str) -> Vec<Suggestion> {
+        let max_distance = match term.chars().count() {
+            0..=2 => return Vec::new(),
+            3..=5 => 1,
+            _ => 2,
+        };
+        let own_freq = vocabulary.get(term).copied().unwrap_or_default();
+        let mut res: Vec<Suggestion> = vocabulary
+            .iter()
+            .filter(|(_, &doc_freq)| doc_freq > own_freq)
+            .filter_map(|(&candidate, &doc_freq)| {
+                let distance = edit_distance(term, candidate, max_distance)?;
+                Some(Suggestion {
+                    term: candidate.to_string(),
+                    word: self
+                        .surface_form(candidate)
+                        .unwrap_or(candidate)
+                        .to_string(),
+                    distance,
+                    doc_freq,
+                })
+            })
+            .collect();
+        res.sort_by(|a, b| {
+            a.distance
+                .cmp(&b.distance)
+                .then(b.doc_freq.cmp(&a.doc_freq))
+                .then(a.term.cmp(&b.term))
+        });
+        res
+    }
+}
+
+/// Applies the case of a word to its lowercase replacement: all caps for an all caps word
+/// of several characters, title case for a capitalized word.
+fn with_case(original: &str, word: &str) -> String {
+    let mut chars = original.chars();
+    let first_upper = chars.next().is_some_and(char::is_uppercase);
+    if first_upper && original.chars().count() > 1 && !chars.any(char::is_lowercase) {
+        return word.to_uppercase();
+    }
+    let mut word_chars = word.chars();
+    match word_chars.next() {
+        Some(first) if first_upper => first.to_uppercase().chain(word_chars).collect(),
+        _ => word.to_string(),
+    }
+}
+
+/// Returns the Damerau-Levenshtein distance between two words with adjacent
+/// transpositions, `None` if it exceeds `max`.
+pub(crate) fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    // Rows of distances between prefixes of `a` and all prefixes of `b`.
+    let mut before: Vec<usize> = Vec::new();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        if current.iter().all(|&d| d > max) {
+            return None;
+        }
+        before = std::mem::replace(&mut previous, current);
+    }
+    Some(previous[b.len()]).filter(|&d| d <= max)
+}
+
+#[cfg(test)]
+mod spelling_tests {
+    use crate::detect::LanguageDetector;
+    use crate::filters::Language;
+    use crate::index::{Document, InvertedIndex};
+    use crate::spelling::edit_distance;
+    use crate::test_support::{docs, FOX_TEXTS};
+
+    fn index(texts: &[&str]) -> InvertedIndex {
+        let mut idx = InvertedIndex::default();
+        idx.add(&docs(texts));
+        idx
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("quick", "quikc", 2), Some(1), "swap failed");
+        assert_eq!(edit_distance("brown", "brwn", 2), Some(1), "delete failed");
+        assert_eq!(
+            edit_distance("kitten", "sitting", 3),
+            Some(3),
+            "distance failed"
+        );
+        assert_eq!(
+            edit_distance("kitten", "sitting", 2),
+            None,
+            "max distance failed"
+        );
+    }
+
+    #[test]
+    fn test_suggest_by_doc_freq() {
+        let idx = index(&["a cat", "the cat sat", "a car"]);
+        let res: Vec<(String, usize)> = idx
+            .suggest("cax")
+            .into_iter()
+            .map(|s| (s.term, s.doc_freq))
+            .collect();
+        let expected = [("cat".to_string(), 2), ("car".to_string(), 1)];
+        assert_eq!(res, expected, "suggestions are not ranked by doc freq");
+        assert!(idx.suggest("cat").is_empty(), "frequent term corrected");
+
+        let idx = index(&["lazy", "lazy", "laziness"]);
+        let res: Vec<(String, String)> = idx
+            .suggest("lazzy")
+            .into_iter()
+            .map(|s| (s.term, s.word))
+            .collect();
+        let expected = [("lazi".to_string(), "lazy".to_string())];
+        assert_eq!(res, expected, "suggestion is not a word");
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        let idx = index(&FOX_TEXTS);
+        assert_eq!(
+            idx.did_you_mean("Quikc brwn fox!"),
+            Some("Quick brown fox!".to_string()),
+            "whole query correction failed"
+        );
+        assert_eq!(idx.did_you_mean("lazy dogs"), None, "correct query changed");
+        let corrected = idx.did_you_mean("lazzy sumer").unwrap();
+        assert_eq!(
+            corrected, "lazy summer",
+            "stem is suggested instead of a word"
+        );
+        assert_eq!(idx.search(&corrected), [2], "corrected query failed");
+    }
+
+    #[test]
+    fn test_did_you_mean_detected_language() {
+        let detector = LanguageDetector::new([Language::English, Language::German]);
+        let mut idx = InvertedIndex::with_language_detection(detector, Language::English);
+        idx.add(&[
+            Document::new(1, "The children were playing in the gardens"),
+            Document::new(2, "Die Kinder spielten in den Gärten"),
+            Document::new(3, "Die Kinder spielten mit dem Hund"),
+        ]);
+        assert_eq!(
+            idx.did_you_mean("Die Kindr spielten"),
+            Some("Die Kinder spielten".to_string()),
+            "german correction failed"
+        );
+        assert_eq!(
+            idx.did_you_mean("The childern were playing"),
+            Some("The children were playing".to_string()),
+            "english correction failed"
+        );
+        assert_eq!(
+            idx.did_you_mean("THE CHILDERN"),
+            Some("THE CHILDREN".to_string()),
+            "all caps correction failed"
+        );
+    }
+}
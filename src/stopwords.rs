@@ -1,6 +1,6 @@
 /*
-All stop word lists except ARABIC, GREEK, ROMANIAN, TAMIL, TURKISH are from the Snowball project
-(https://snowballstem.org/) which carries the BSD License. | See https://snowballstem.org/license.html
+All stop word lists except ARABIC, GREEK, ROMANIAN, TAMIL, TURKISH and the ones listed below are from
+the Snowball project (https://snowballstem.org/) which carries the BSD License. | See https://snowballstem.org/license.html
 
 ARABIC, GREEK, ROMANIAN and TURKISH stop word lists gathered from Stopwords ISO project
 (https://github.com/stopwords-iso) which caries the MIT license | See:
@@ -8,6 +8,16 @@ https://github.com/stopwords-iso/stopwords-ar
 https://github.com/stopwords-iso/stopwords-el
 https://github.com/stopwords-iso/stopwords-ro
 https://github.com/stopwords-iso/stopwords-tr
+
+ARMENIAN, BASQUE, CATALAN, INDONESIAN, IRISH, LITHUANIAN, NEPALI and YIDDISH stop word lists are
+compiled by hand for this crate and not taken from a published list. They only contain common
+function words: articles, pronouns, conjunctions, prepositions and auxiliary verbs.
+The Stopwords ISO project (https://github.com/stopwords-iso) has longer lists for most of them.
+
+CHINESE, JAPANESE and KOREAN stop word lists are the most frequent entries of Stopwords ISO lists | See:
+https://github.com/stopwords-iso/stopwords-zh
+https://github.com/stopwords-iso/stopwords-ja
+https://github.com/stopwords-iso/stopwords-ko
 */
 
 /// This file contains stop words for all supported languages.
@@ -494,6 +504,181 @@ pub const ARABIC: &[&str] = &[
     "ّأيّان",
 ];
 
+pub const ARMENIAN: &[&str] = &[
+    "և",
+    "ու",
+    "է",
+    "են",
+    "էր",
+    "էին",
+    "եմ",
+    "ես",
+    "ենք",
+    "եք",
+    "լինել",
+    "այս",
+    "այդ",
+    "այն",
+    "այսպես",
+    "որ",
+    "որը",
+    "ով",
+    "ինչ",
+    "ինչպես",
+    "թե",
+    "բայց",
+    "կամ",
+    "նա",
+    "նրանք",
+    "դու",
+    "մենք",
+    "դուք",
+    "իմ",
+    "քո",
+    "նրա",
+    "մեր",
+    "ձեր",
+    "նրանց",
+    "իր",
+    "իրենց",
+    "մի",
+    "մեկ",
+    "հետ",
+    "համար",
+    "մասին",
+    "վրա",
+    "մեջ",
+    "մինչև",
+    "առանց",
+    "ոչ",
+    "չէ",
+    "այո",
+    "արդեն",
+    "դեռ",
+    "միայն",
+    "նաև",
+    "ամեն",
+    "բոլոր",
+    "շատ",
+    "քիչ",
+    "այստեղ",
+    "այնտեղ",
+    "երբ",
+    "որտեղ",
+    "ապա",
+    "իսկ",
+    "եթե",
+    "քան",
+    "պետք",
+];
+
+pub const BASQUE: &[&str] = &[
+    "eta", "ez", "da", "dira", "zen", "ziren", "du", "dute", "zuen", "zuten", "bat", "batzuk",
+    "hau", "hori", "hura", "hauek", "horiek", "haiek", "ni", "zu", "gu", "zuek", "nire", "zure",
+    "bere", "gure", "beren", "baina", "edo", "ere", "bai", "oso", "gehiago", "guztiak", "dena",
+    "non", "nor", "zer", "nola", "noiz", "zergatik", "baino", "arte", "gabe", "gainean", "ondoren",
+    "aurretik", "izan", "egin", "dago", "daude", "zegoen", "hemen", "han", "hor", "orain", "gero",
+    "beti", "inoiz", "ala", "edota", "baita", "baizik", "ba", "al", "bezala", "beste", "beraz",
+    "hala", "honela",
+];
+
+pub const CATALAN: &[&str] = &[
+    "a",
+    "abans",
+    "al",
+    "als",
+    "amb",
+    "aquell",
+    "aquella",
+    "aquelles",
+    "aquells",
+    "aquest",
+    "aquesta",
+    "aquestes",
+    "aquests",
+    "així",
+    "com",
+    "de",
+    "del",
+    "dels",
+    "des",
+    "després",
+    "el",
+    "els",
+    "en",
+    "entre",
+    "era",
+    "es",
+    "és",
+    "està",
+    "et",
+    "fins",
+    "ha",
+    "han",
+    "havia",
+    "hem",
+    "hi",
+    "ho",
+    "i",
+    "ja",
+    "jo",
+    "la",
+    "les",
+    "li",
+    "ma",
+    "mateix",
+    "me",
+    "més",
+    "meu",
+    "meva",
+    "molt",
+    "ni",
+    "no",
+    "nosaltres",
+    "o",
+    "on",
+    "per",
+    "però",
+    "perquè",
+    "poc",
+    "quan",
+    "que",
+    "qui",
+    "sa",
+    "se",
+    "sempre",
+    "sense",
+    "ses",
+    "seu",
+    "seus",
+    "seva",
+    "seves",
+    "si",
+    "sí",
+    "sobre",
+    "són",
+    "també",
+    "te",
+    "tot",
+    "tots",
+    "tu",
+    "un",
+    "una",
+    "unes",
+    "uns",
+    "va",
+    "van",
+    "vosaltres",
+];
+
+pub const CHINESE: &[&str] = &[
+    "一", "上", "不", "也", "了", "人", "他", "你", "们", "到", "和", "在", "她", "就", "我", "是",
+    "有", "的", "而", "要", "说", "这", "那", "都", "着", "么", "之", "与", "及", "或", "被", "把",
+    "从", "对", "让", "吗", "吧", "呢", "啊", "它", "我们", "你们", "他们", "这个", "那个", "因为",
+    "所以", "但是", "如果", "可以", "没有", "什么", "自己", "已经", "就是", "还是", "或者", "以及",
+    "而且", "不过", "然后",
+];
+
 pub const DANISH: &[&str] = &[
     "og", "i", "jeg", "det", "at", "en", "den", "til", "er", "som", "på", "de", "med", "han", "af",
     "for", "ikke", "der", "var", "mig", "sig", "men", "et", "har", "om", "vi", "min", "havde",
@@ -2063,6 +2248,25 @@ pub const HUNGARIAN: &[&str] = &[
     "volna",
 ];
 
+pub const INDONESIAN: &[&str] = &[
+    "ada", "adalah", "agar", "akan", "aku", "anda", "antara", "apa", "atas", "atau", "bagi",
+    "bahwa", "banyak", "begitu", "belum", "bila", "bisa", "dalam", "dan", "dari", "dengan", "di",
+    "dia", "hanya", "harus", "hingga", "ia", "ini", "itu", "jika", "juga", "kalau", "kami", "kamu",
+    "karena", "ke", "kepada", "ketika", "kita", "lagi", "lebih", "maka", "masih", "mereka",
+    "namun", "oleh", "pada", "para", "pun", "saat", "sangat", "saya", "sebagai", "sebuah",
+    "secara", "sedang", "sejak", "sekarang", "semua", "seorang", "serta", "setelah", "sudah",
+    "tapi", "telah", "tentang", "tetapi", "tidak", "untuk", "yang",
+];
+
+pub const IRISH: &[&str] = &[
+    "a", "ach", "ag", "agus", "air", "an", "ar", "arna", "as", "ba", "bhí", "chomh", "chuig",
+    "chun", "cois", "dá", "de", "den", "do", "dó", "don", "é", "faoi", "faoin", "gach", "gan",
+    "go", "i", "í", "iad", "idir", "in", "ina", "inár", "is", "le", "leat", "léi", "leis", "lena",
+    "leo", "libh", "linn", "liom", "mar", "mo", "muid", "na", "ná", "nach", "ní", "níl", "nó", "ó",
+    "orainn", "orm", "ort", "os", "sa", "san", "sé", "sí", "siad", "sibh", "tá", "tar", "trí",
+    "tú", "uirthi", "um",
+];
+
 pub const ITALIAN: &[&str] = &[
     "ad",
     "al",
@@ -2345,6 +2549,178 @@ pub const ITALIAN: &[&str] = &[
     "stando",
 ];
 
+pub const JAPANESE: &[&str] = &[
+    "あそこ",
+    "あの",
+    "あれ",
+    "いる",
+    "う",
+    "え",
+    "お",
+    "か",
+    "が",
+    "これ",
+    "この",
+    "こと",
+    "さ",
+    "し",
+    "す",
+    "する",
+    "そこ",
+    "その",
+    "それ",
+    "た",
+    "だ",
+    "て",
+    "で",
+    "と",
+    "な",
+    "に",
+    "の",
+    "は",
+    "へ",
+    "ます",
+    "まで",
+    "も",
+    "もの",
+    "や",
+    "よ",
+    "より",
+    "ら",
+    "る",
+    "れ",
+    "を",
+    "ん",
+    "ある",
+    "いう",
+    "から",
+    "ため",
+    "です",
+    "ない",
+    "なる",
+    "よう",
+    "として",
+    "において",
+];
+
+pub const KOREAN: &[&str] = &[
+    "가",
+    "것",
+    "게",
+    "고",
+    "과",
+    "그",
+    "그리고",
+    "그러나",
+    "그래서",
+    "는",
+    "다",
+    "도",
+    "들",
+    "등",
+    "및",
+    "를",
+    "만",
+    "또",
+    "또는",
+    "수",
+    "에",
+    "에서",
+    "와",
+    "요",
+    "으로",
+    "은",
+    "을",
+    "의",
+    "이",
+    "있다",
+    "저",
+    "좀",
+    "하다",
+    "한",
+    "할",
+    "해",
+    "했다",
+    "하고",
+    "하지만",
+    "때문에",
+];
+
+pub const LITHUANIAN: &[&str] = &[
+    "ir", "bet", "kad", "o", "ar", "nes", "jei", "jeigu", "tai", "kaip", "kur", "kas", "kuris",
+    "kuri", "kurie", "kurios", "tas", "ta", "tie", "tos", "šis", "ši", "šie", "šios", "aš", "tu",
+    "jis", "ji", "mes", "jūs", "jie", "jos", "mano", "tavo", "savo", "jo", "jų", "yra", "buvo",
+    "bus", "būti", "į", "iš", "su", "be", "per", "prie", "po", "apie", "ant", "už", "nuo", "iki",
+    "dėl", "tarp", "prieš", "ne", "taip", "dar", "jau", "tik", "labai", "net", "čia", "ten", "kai",
+    "nors", "arba", "bei", "ką", "kurį", "tą", "jį", "ją", "juos", "jas", "man", "tau", "jam",
+    "jai", "mums", "jums", "jiems", "joms", "visi", "visos", "viskas",
+];
+
+pub const NEPALI: &[&str] = &[
+    "छ",
+    "छन्",
+    "हो",
+    "हुन्",
+    "थियो",
+    "थिए",
+    "र",
+    "पनि",
+    "को",
+    "का",
+    "की",
+    "मा",
+    "ले",
+    "लाई",
+    "बाट",
+    "देखि",
+    "सँग",
+    "तथा",
+    "वा",
+    "भने",
+    "यो",
+    "त्यो",
+    "यी",
+    "ती",
+    "म",
+    "हामी",
+    "तिमी",
+    "तपाईं",
+    "उनी",
+    "उहाँ",
+    "ऊ",
+    "उनको",
+    "मेरो",
+    "हाम्रो",
+    "तर",
+    "भएको",
+    "गर्न",
+    "गरेको",
+    "गर्छ",
+    "हुने",
+    "भन्ने",
+    "लागि",
+    "अनि",
+    "नै",
+    "पछि",
+    "अघि",
+    "माथि",
+    "तल",
+    "जुन",
+    "जो",
+    "के",
+    "कसरी",
+    "किन",
+    "कहाँ",
+    "कुन",
+    "अरू",
+    "सबै",
+    "धेरै",
+    "एक",
+    "रहेको",
+    "गरी",
+    "गरे",
+];
+
 pub const NORWEGIAN: &[&str] = &[
     "og",
     "i",
@@ -4283,3 +4659,55 @@ pub const TURKISH: &[&str] = &[
     "ţu",
     "ţöyle",
 ];
+
+pub const YIDDISH: &[&str] = &[
+    "און",
+    "אַ",
+    "אַן",
+    "דער",
+    "די",
+    "דאָס",
+    "דעם",
+    "איז",
+    "זענען",
+    "געווען",
+    "האָט",
+    "האָבן",
+    "ער",
+    "זי",
+    "עס",
+    "איך",
+    "דו",
+    "מיר",
+    "איר",
+    "זיי",
+    "מיין",
+    "דיין",
+    "זיין",
+    "אונדזער",
+    "אײַער",
+    "זייער",
+    "אין",
+    "אויף",
+    "פֿון",
+    "מיט",
+    "צו",
+    "פֿאַר",
+    "נישט",
+    "ניט",
+    "ווי",
+    "וואָס",
+    "ווער",
+    "ווען",
+    "וווּ",
+    "אַז",
+    "אָבער",
+    "אָדער",
+    "אויך",
+    "שוין",
+    "נאָר",
+    "נאָך",
+    "דאָ",
+    "דאָרט",
+    "אַלע",
+];
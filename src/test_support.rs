@@ -0,0 +1,23 @@
+/// Fixtures shared by tests of several modules.
+use crate::index::Document;
+
+/// Texts of the two documents indexed by most tests.
+pub(crate) const FOX_TEXTS: [&str; 2] = [
+    "The quick brown fox jumped over the lazy dog",
+    "Quick brown foxes leap over lazy dogs in summer",
+];
+
+/// Creates documents of texts with ids 1, 2 and so on.
+pub(crate) fn docs(texts: &[&str]) -> Vec<Document> {
+    texts
+        .iter()
+        .zip(1..)
+        .map(|(text, id)| Document::new(id, *text))
+        .collect()
+}
+
+/// Creates documents of `FOX_TEXTS` with ids 1 and 2.
+pub(crate) fn fox_docs() -> [Document; 2] {
+    let [first, second] = FOX_TEXTS;
+    [Document::new(1, first), Document::new(2, second)]
+}
@@ -1,9 +1,49 @@
 /// Tokenizer converts text to tokens using a Unicode Text Segmentation technique.
 /// For more details, see https://www.unicode.org/reports/tr29/
+use std::ops::Range;
 use unicode_segmentation::UnicodeSegmentation;
 
+/// `Token` is a term produced by a tokenizer.
+/// `text`: is a term itself
+/// `position`: is a position of the term in a text, terms produced from one word share its position
+/// `offset`: is a byte range of the word the term was produced from in the original text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub text: String,
+    pub position: usize,
+    pub offset: Range<usize>,
+}
+
+impl Token {
+    /// Creates a token from a part of the token text at the same position.
+    /// The offset is exact only if the token text is unchanged since tokenization.
+    pub(crate) fn subword(&self, range: Range<usize>) -> Token {
+        let offset = if self.text.len() == self.offset.len() {
+            self.offset.start + range.start..self.offset.start + range.end
+        } else {
+            self.offset.clone()
+        };
+        Token {
+            text: self.text[range].to_string(),
+            position: self.position,
+            offset,
+        }
+    }
+}
+
+/// `Tokenize` is implemented by all tokenizers, so they can be used in an `Analyzer`.
+pub trait Tokenize: Send + Sync {
+    /// Converts text to tokens with positions.
+    fn tokens(&self, text: &str) -> Vec<Token>;
+}
+
 pub struct Tokenizer {}
 
+/// `WhitespaceTokenizer` splits text on whitespace only, punctuation stays in tokens,
+/// e.g. "wi-fi" is a single token.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WhitespaceTokenizer;
+
 impl Default for Tokenizer {
     fn default() -> Self {
         Self::new()
@@ -24,9 +64,54 @@ impl Tokenizer {
     }
 }
 
+impl Tokenize for Tokenizer {
+    fn tokens(&self, text: &str) -> Vec<Token> {
+        text.unicode_word_indices()
+            .enumerate()
+            .map(|(position, (start, word))| Token {
+                text: word.to_string(),
+                position,
+                offset: start..start + word.len(),
+            })
+            .collect()
+    }
+}
+
+impl WhitespaceTokenizer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tokenize for WhitespaceTokenizer {
+    fn tokens(&self, text: &str) -> Vec<Token> {
+        let mut offsets = Vec::new();
+        let mut start = None;
+        for (i, c) in text
+            .char_indices()
+            .chain(std::iter::once((text.len(), ' ')))
+        {
+            if !c.is_whitespace() {
+                start = start.or(Some(i));
+            } else if let Some(start) = start.take() {
+                offsets.push(start..i);
+            }
+        }
+        offsets
+            .into_iter()
+            .enumerate()
+            .map(|(position, offset)| Token {
+                text: text[offset.clone()].to_string(),
+                position,
+                offset,
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tokenizer_tests {
-    use crate::tokenizer::Tokenizer;
+    use crate::tokenizer::{Token, Tokenize, Tokenizer, WhitespaceTokenizer};
 
     #[test]
     fn test_tokenize() {
@@ -38,4 +123,38 @@ mod tokenizer_tests {
         ];
         assert_eq!(res, expected, "tokenization failed");
     }
+
+    #[test]
+    fn test_tokens() {
+        let tokenizer = Tokenizer::new();
+        let res = tokenizer.tokens("Hello, world!");
+        let expected = [
+            Token {
+                text: "Hello".to_string(),
+                position: 0,
+                offset: 0..5,
+            },
+            Token {
+                text: "world".to_string(),
+                position: 1,
+                offset: 7..12,
+            },
+        ];
+        assert_eq!(res, expected, "tokens with positions failed");
+    }
+
+    #[test]
+    fn test_whitespace_tokens() {
+        let tokenizer = WhitespaceTokenizer::new();
+        let res = tokenizer.tokens("  wi-fi  SD500!");
+        let texts: Vec<(&str, usize)> = res
+            .iter()
+            .map(|t| (t.text.as_str(), t.offset.start))
+            .collect();
+        assert_eq!(
+            texts,
+            [("wi-fi", 2), ("SD500!", 9)],
+            "whitespace tokenization failed"
+        );
+    }
 }
@@ -0,0 +1,542 @@
+/// This is the place where the write-ahead log is implemented.
+/// Every change of an index is appended to the log before it is applied,
+/// so documents can be recovered by replaying the log after a crash.
+///
+/// The log is a sequence of records: `[payload length: u32][crc32 of payload: u32][payload]`.
+/// A payload is an operation tag followed by a doc id and, for additions, the document text.
+/// Additions of documents with named fields have a field count and every field as
+/// `[name length: u32][name][text length: u32][text]` before the document text.
+/// Integers are little-endian.
+/// The log only grows, so it should be checkpointed from time to time to bound its size
+/// and the time to replay it.
+use crate::filters::Language;
+use crate::index::{Document, InvertedIndex};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const ADD: u8 = 0;
+const DELETE: u8 = 1;
+const ADD_FIELDS: u8 = 2;
+const HEADER_LEN: usize = 8;
+
+/// `Operation` is a change of an index recorded in the log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Operation {
+    Add(Document),
+    Delete(u64),
+}
+
+/// `SyncPolicy` controls when the log is flushed to disk with fsync.
+/// `Always`: after every append, nothing acknowledged is lost
+/// `Every(n)`: after every `n` operations, up to `n - 1` operations may be lost on power failure
+/// `Never`: left to the operating system, survives a process crash but not a power failure
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncPolicy {
+    Always,
+    Every(usize),
+    Never,
+}
+
+/// `WriteAheadLog` is an append-only file of index operations.
+pub struct WriteAheadLog {
+    path: PathBuf,
+    file: File,
+    policy: SyncPolicy,
+    unsynced: usize,
+    len: u64,
+}
+
+/// `DurableIndex` is an `InvertedIndex` which logs every change before applying it.
+pub struct DurableIndex {
+    index: InvertedIndex,
+    wal: WriteAheadLog,
+}
+
+impl WriteAheadLog {
+    /// Opens the log or creates an empty one and returns all operations recorded in it.
+    /// Replay stops at the first incomplete or corrupted record, such a tail
+    /// is left by a crash in the middle of a write and is cut off the log.
+    pub fn open<P: AsRef<Path>>(path: P, policy: SyncPolicy) -> io::Result<(Self, Vec<Operation>)> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+        let (ops, valid) = read_ops(&mut file)?;
+        if valid < file.metadata()?.len() {
+            file.set_len(valid)?;
+            file.sync_all()?;
+        }
+        file.seek(SeekFrom::End(0))?;
+
+        let wal = WriteAheadLog {
+            path,
+            file,
+            policy,
+            unsynced: 0,
+            len: valid,
+        };
+        Ok((wal, ops))
+    }
+
+    /// Appends operations to the log and syncs it according to the policy.
+    /// A partially written batch is cut off, so later appends are not hidden behind it.
+    pub fn append(&mut self, ops: &[Operation]) -> io::Result<()> {
+        let mut buf = Vec::new();
+        for op in ops {
+            encode(op, &mut buf)?;
+        }
+        if let Err(err) = self.file.write_all(&buf) {
+            let _ = self.file.set_len(self.len);
+            let _ = self.file.seek(SeekFrom::End(0));
+            return Err(err);
+        }
+        self.len += buf.len() as u64;
+        self.unsynced += ops.len();
+
+        match self.policy {
+            SyncPolicy::Always => self.sync(),
+            SyncPolicy::Every(n) if self.unsynced >= n => self.sync(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Flushes all appended operations to disk.
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.file.sync_data()?;
+        self.unsynced = 0;
+        Ok(())
+    }
+
+    /// Returns all operations recorded in the log.
+    pub fn operations(&mut self) -> io::Result<Vec<Operation>> {
+        let (ops, _) = read_ops(&mut self.file)?;
+        self.file.seek(SeekFrom::End(0))?;
+        Ok(ops)
+    }
+
+    /// Replaces the log with `ops`, e.g. a snapshot of the index state.
+    /// The new log is written to a temporary file which is renamed over the log,
+    /// so a crash leaves either the old or the new log.
+    pub fn checkpoint(&mut self, ops: &[Operation]) -> io::Result<()> {
+        let mut buf = Vec::new();
+        for op in ops {
+            encode(op, &mut buf)?;
+        }
+        let mut tmp_path = OsString::from(&self.path);
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        let mut tmp = File::create(&tmp_path)?;
+        tmp.write_all(&buf)?;
+        tmp.sync_all()?;
+        drop(tmp);
+        fs::rename(&tmp_path, &self.path)?;
+        // The rename is durable only once the directory entry is flushed too.
+        #[cfg(unix)]
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            File::open(dir)?.sync_all()?;
+        }
+
+        self.file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&self.path)?;
+        self.len = buf.len() as u64;
+        self.unsynced = 0;
+        Ok(())
+    }
+
+    /// Removes all operations from the log,
+    /// to be called once the state they describe is persisted elsewhere.
+    pub fn truncate(&mut self) -> io::Result<()> {
+        self.file.set_len(0)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.sync_all()?;
+        self.len = 0;
+        self.unsynced = 0;
+        Ok(())
+    }
+}
+
+impl DurableIndex {
+    /// Opens index with custom language, documents recorded in the log are indexed again.
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        language: Language,
+        policy: SyncPolicy,
+    ) -> io::Result<Self> {
+        let (wal, ops) = WriteAheadLog::open(path, policy)?;
+        let mut index = InvertedIndex::new(language);
+        for op in ops {
+            match op {
+                Operation::Add(doc) => index.add(&[doc]),
+                Operation::Delete(id) => index.delete(id),
+            }
+        }
+        Ok(DurableIndex { index, wal })
+    }
+
+    /// Logs and indexes documents.
+    pub fn add(&mut self, docs: &[Document]) -> io::Result<()> {
+        let ops: Vec<Operation> = docs.iter().cloned().map(Operation::Add).collect();
+        self.wal.append(&ops)?;
+        self.index.add(docs);
+        Ok(())
+    }
+
+    /// Logs and removes document.
+    pub fn delete(&mut self, id: u64) -> io::Result<()> {
+        self.wal.append(&[Operation::Delete(id)])?;
+        self.index.delete(id);
+        Ok(())
+    }
+
+    /// Flushes the log to disk regardless of the sync policy.
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.wal.sync()
+    }
+
+    /// Compacts the log to the last addition of every live document,
+    /// so deleted and replaced documents are no longer replayed on open.
+    pub fn checkpoint(&mut self) -> io::Result<()> {
+        let mut live: HashMap<u64, Document> = HashMap::new();
+        let mut order = Vec::new();
+        for op in self.wal.operations()? {
+            match op {
+                Operation::Add(doc) => {
+                    order.push(doc.id);
+                    live.insert(doc.id, doc);
+                }
+                Operation::Delete(id) => {
+                    live.remove(&id);
+                }
+            }
+        }
+        // A document is kept at the position of its last addition.
+        let mut ops = Vec::with_capacity(live.len());
+        for id in order.into_iter().rev() {
+            if let Some(doc) = live.remove(&id) {
+                ops.push(Operation::Add(doc));
+            }
+        }
+        ops.reverse();
+        self.wal.checkpoint(&ops)
+    }
+
+    /// Index with all logged changes applied, used for searching.
+    pub fn index(&self) -> &InvertedIndex {
+        &self.index
+    }
+}
+
+/// Reads the valid records from the start of the file,
+/// returns their operations and the length of the valid part.
+fn read_ops(file: &mut File) -> io::Result<(Vec<Operation>, u64)> {
+    let mut buf = Vec::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_end(&mut buf)?;
+
+    let mut ops = Vec::new();
+    let mut valid = 0;
+    while let Some((op, len)) = decode(&buf[valid..]) {
+        ops.push(op);
+        valid += len;
+    }
+    Ok((ops, valid as u64))
+}
+
+/// Appends a record of the operation to `buf`.
+/// Fails if the payload doesn't fit into the length field of the header.
+fn encode(op: &Operation, buf: &mut Vec<u8>) -> io::Result<()> {
+    let mut payload = Vec::new();
+    match op {
+        Operation::Add(doc) if doc.fields.is_empty() => {
+            payload.push(ADD);
+            payload.extend_from_slice(&doc.id.to_le_bytes());
+            payload.extend_from_slice(doc.text.as_bytes());
+        }
+        Operation::Add(doc) => {
+            payload.push(ADD_FIELDS);
+            payload.extend_from_slice(&doc.id.to_le_bytes());
+            payload.extend_from_slice(&payload_len(doc.fields.len())?.to_le_bytes());
+            for (name, text) in &doc.fields {
+                for bytes in [name.as_bytes(), text.as_bytes()] {
+                    payload.extend_from_slice(&payload_len(bytes.len())?.to_le_bytes());
+                    payload.extend_from_slice(bytes);
+                }
+            }
+            payload.extend_from_slice(doc.text.as_bytes());
+        }
+        Operation::Delete(id) => {
+            payload.push(DELETE);
+            payload.extend_from_slice(&id.to_le_bytes());
+        }
+    }
+    buf.extend_from_slice(&payload_len(payload.len())?.to_le_bytes());
+    buf.extend_from_slice(&crc32(&payload).to_le_bytes());
+    buf.extend_from_slice(&payload);
+    Ok(())
+}
+
+fn payload_len(len: usize) -> io::Result<u32> {
+    u32::try_from(len).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("record payload of {len} bytes exceeds the 4 GiB limit"),
+        )
+    })
+}
+
+/// Decodes the first record of `buf`, returns the operation and the record length.
+/// Returns `None` if the record is incomplete or corrupted.
+fn decode(buf: &[u8]) -> Option<(Operation, usize)> {
+    let header = buf.get(..HEADER_LEN)?;
+    let len = u32::from_le_bytes(header[..4].try_into().ok()?) as usize;
+    let crc = u32::from_le_bytes(header[4..].try_into().ok()?);
+    let payload = buf.get(HEADER_LEN..HEADER_LEN.checked_add(len)?)?;
+    if crc32(payload) != crc {
+        return None;
+    }
+
+    let (&tag, rest) = payload.split_first()?;
+    let id = u64::from_le_bytes(rest.get(..8)?.try_into().ok()?);
+    let op = match tag {
+        ADD => {
+            let text = String::from_utf8(rest[8..].to_vec()).ok()?;
+            Operation::Add(Document::new(id, text))
+        }
+        ADD_FIELDS => Operation::Add(decode_fields(id, &rest[8..])?),
+        DELETE if rest.len() == 8 => Operation::Delete(id),
+        _ => return None,
+    };
+    Some((op, HEADER_LEN + len))
+}
+
+/// Decodes a document with named fields from the payload after the doc id.
+fn decode_fields(id: u64, mut buf: &[u8]) -> Option<Document> {
+    let count = u32::from_le_bytes(take(&mut buf, 4)?.try_into().ok()?);
+    let mut fields = Vec::new();
+    for _ in 0..count {
+        fields.push((take_string(&mut buf)?, take_string(&mut buf)?));
+    }
+    let text = String::from_utf8(buf.to_vec()).ok()?;
+    Some(Document { id, text, fields })
+}
+
+/// Cuts `len` bytes off the start of `buf`.
+fn take<'a>(buf: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    let (head, tail) = buf.split_at_checked(len)?;
+    *buf = tail;
+    Some(head)
+}
+
+/// Cuts a string prefixed with its length off the start of `buf`.
+fn take_string(buf: &mut &[u8]) -> Option<String> {
+    let len = u32::from_le_bytes(take(buf, 4)?.try_into().ok()?) as usize;
+    String::from_utf8(take(buf, len)?.to_vec()).ok()
+}
+
+/// CRC-32 (IEEE 802.3) checksum.
+fn crc32(bytes: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xEDB8_8320
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    !bytes.iter().fold(!0u32, |crc, &byte| {
+        TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod wal_tests {
+    use crate::filters::Language;
+    use crate::index::Document;
+    use crate::test_support::fox_docs;
+    use crate::wal::{crc32, payload_len, DurableIndex, Operation, SyncPolicy, WriteAheadLog};
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    /// Log file in a temporary directory, removed when dropped.
+    struct TempLog(PathBuf);
+
+    impl TempLog {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("wal-{}-{name}.log", std::process::id()));
+            let _ = fs::remove_file(&path);
+            TempLog(path)
+        }
+    }
+
+    impl Drop for TempLog {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926, "crc32 failed");
+    }
+
+    #[test]
+    fn test_replay() {
+        let log = TempLog::new("replay");
+        {
+            let mut idx =
+                DurableIndex::open(&log.0, Language::English, SyncPolicy::Always).unwrap();
+            idx.add(&fox_docs()).unwrap();
+            idx.delete(1).unwrap();
+        }
+        let idx = DurableIndex::open(&log.0, Language::English, SyncPolicy::Always).unwrap();
+        assert_eq!(idx.index().search("brown foxes"), [2], "replay failed");
+    }
+
+    #[test]
+    fn test_replay_fields() {
+        let log = TempLog::new("fields");
+        let doc = Document::new(1, "The quick brown fox")
+            .with_field("title", "Foxes")
+            .with_field("tags", "");
+        {
+            let (mut wal, _) = WriteAheadLog::open(&log.0, SyncPolicy::Always).unwrap();
+            wal.append(&[Operation::Add(doc.clone()), Operation::Delete(2)])
+                .unwrap();
+        }
+        let (_, ops) = WriteAheadLog::open(&log.0, SyncPolicy::Always).unwrap();
+        assert_eq!(
+            ops,
+            [Operation::Add(doc), Operation::Delete(2)],
+            "replaying fields failed"
+        );
+    }
+
+    #[test]
+    fn test_truncated_tail() {
+        let log = TempLog::new("truncated");
+        {
+            let (mut wal, _) = WriteAheadLog::open(&log.0, SyncPolicy::Never).unwrap();
+            let ops = fox_docs().map(Operation::Add);
+            wal.append(&ops).unwrap();
+        }
+        let len = fs::metadata(&log.0).unwrap().len();
+        for cut in [1, 5, 12] {
+            let file = OpenOptions::new().write(true).open(&log.0).unwrap();
+            file.set_len(len - cut).unwrap();
+            drop(file);
+
+            let (_, ops) = WriteAheadLog::open(&log.0, SyncPolicy::Never).unwrap();
+            assert_eq!(
+                ops,
+                [Operation::Add(fox_docs()[0].clone())],
+                "torn record replayed"
+            );
+            let (_, ops) = WriteAheadLog::open(&log.0, SyncPolicy::Never).unwrap();
+            assert_eq!(ops.len(), 1, "torn record is not cut off");
+
+            let (mut wal, _) = WriteAheadLog::open(&log.0, SyncPolicy::Never).unwrap();
+            wal.append(&[Operation::Add(fox_docs()[1].clone())])
+                .unwrap();
+        }
+        let (_, ops) = WriteAheadLog::open(&log.0, SyncPolicy::Never).unwrap();
+        assert_eq!(
+            ops,
+            fox_docs().map(Operation::Add),
+            "append after recovery failed"
+        );
+    }
+
+    #[test]
+    fn test_corrupted_tail() {
+        let log = TempLog::new("corrupted");
+        {
+            let mut idx =
+                DurableIndex::open(&log.0, Language::English, SyncPolicy::Every(2)).unwrap();
+            idx.add(&fox_docs()).unwrap();
+        }
+        let mut file = OpenOptions::new().append(true).open(&log.0).unwrap();
+        file.write_all(&[9, 0, 0, 0, 1, 2, 3, 4, 0, 3, 0, 0, 0, 0, 0, 0, 0])
+            .unwrap();
+        drop(file);
+
+        let idx = DurableIndex::open(&log.0, Language::English, SyncPolicy::Never).unwrap();
+        assert_eq!(idx.index().search("lazy"), [1, 2], "recovery failed");
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let log = TempLog::new("checkpoint");
+        {
+            let mut idx =
+                DurableIndex::open(&log.0, Language::English, SyncPolicy::Always).unwrap();
+            idx.add(&fox_docs()).unwrap();
+            idx.delete(1).unwrap();
+            idx.add(&[Document::new(3, "A lazy afternoon")]).unwrap();
+            idx.delete(3).unwrap();
+            let len = fs::metadata(&log.0).unwrap().len();
+            idx.checkpoint().unwrap();
+            assert!(
+                fs::metadata(&log.0).unwrap().len() < len,
+                "checkpoint did not shrink the log"
+            );
+            idx.add(&[fox_docs()[0].clone()]).unwrap();
+        }
+        let (mut wal, ops) = WriteAheadLog::open(&log.0, SyncPolicy::Never).unwrap();
+        let expected = [fox_docs()[1].clone(), fox_docs()[0].clone()].map(Operation::Add);
+        assert_eq!(ops, expected, "checkpoint lost operations");
+
+        wal.truncate().unwrap();
+        wal.append(&[Operation::Delete(2)]).unwrap();
+        drop(wal);
+        let (_, ops) = WriteAheadLog::open(&log.0, SyncPolicy::Never).unwrap();
+        assert_eq!(ops, [Operation::Delete(2)], "truncate failed");
+    }
+
+    #[test]
+    fn test_checkpoint_replaced() {
+        let log = TempLog::new("replaced");
+        let before = {
+            let mut idx =
+                DurableIndex::open(&log.0, Language::English, SyncPolicy::Always).unwrap();
+            idx.add(&[Document::new(1, "brown fox")]).unwrap();
+            idx.add(&[Document::new(1, "lazy dog")]).unwrap();
+            let before = [idx.index().search("fox"), idx.index().search("dog")];
+            idx.checkpoint().unwrap();
+            before
+        };
+        let idx = DurableIndex::open(&log.0, Language::English, SyncPolicy::Always).unwrap();
+        let after = [idx.index().search("fox"), idx.index().search("dog")];
+        assert_eq!(before, after, "checkpoint changed search results");
+        assert_eq!(after, [vec![], vec![1]], "replaced document matched");
+    }
+
+    #[test]
+    fn test_payload_len_limit() {
+        assert_eq!(payload_len(17).unwrap(), 17, "payload length failed");
+        assert!(
+            payload_len(u32::MAX as usize + 1).is_err(),
+            "oversized payload is not rejected"
+        );
+    }
+}
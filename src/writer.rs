@@ -0,0 +1,523 @@
+/// This is the place where concurrent access to an index is implemented.
+/// `IndexWriter` buffers documents and publishes them on commit as a new segment,
+/// `IndexReader` hands out point-in-time `Searcher`s over the last published segments.
+/// Segments are merged in background threads chosen by a `MergePolicy`.
+use crate::analyzer::Analyzer;
+use crate::filters::Language;
+use crate::index::{matches, ordered, Document, Page};
+use crate::merge_policy::{MergePolicy, SegmentInfo, TieredMergePolicy};
+use crate::segment::{Segment, SegmentEntry};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::thread::{self, JoinHandle};
+
+/// Committed data, never modified once published.
+struct Snapshot {
+    segments: Vec<SegmentEntry>,
+}
+
+/// State shared by the writer, its readers and merge threads.
+/// The lock is held only to clone or swap the snapshot, so readers never wait on indexing or merging.
+struct Shared {
+    published: RwLock<Arc<Snapshot>>,
+    merging: Mutex<HashSet<u64>>,
+}
+
+/// `IndexWriter` is the single writer of an index.
+/// Added and deleted documents stay invisible to readers until `commit` is called.
+pub struct IndexWriter {
+    analyzer: Arc<Analyzer>,
+    pending: Segment,
+    pending_deletes: HashSet<u64>,
+    shared: Arc<Shared>,
+    merge_policy: Box<dyn MergePolicy>,
+    merge_threads: Vec<JoinHandle<()>>,
+    next_segment_id: u64,
+}
+
+/// `IndexReader` opens searchers over the last committed state of an index.
+/// Readers are cheap to clone and can be sent to other threads.
+#[derive(Clone)]
+pub struct IndexReader {
+    analyzer: Arc<Analyzer>,
+    shared: Arc<Shared>,
+}
+
+/// `Searcher` is a consistent point-in-time view of an index.
+/// Commits and merges made after the searcher was opened are not visible to it.
+pub struct Searcher {
+    analyzer: Arc<Analyzer>,
+    snapshot: Arc<Snapshot>,
+}
+
+/// Creates empty index.
+/// `Default` assume that a text will be in English.
+impl Default for IndexWriter {
+    fn default() -> Self {
+        Self::new(Language::English)
+    }
+}
+
+impl IndexWriter {
+    /// Creates empty index with custom language.
+    /// Segments are merged with `TieredMergePolicy` unless another policy is set.
+    pub fn new(language: Language) -> Self {
+        Self::with_analyzer(Analyzer::new(language))
+    }
+
+    /// Creates empty index with custom analyzer.
+    pub fn with_analyzer(analyzer: Analyzer) -> Self {
+        let snapshot = Snapshot {
+            segments: Vec::new(),
+        };
+        let shared = Shared {
+            published: RwLock::new(Arc::new(snapshot)),
+            merging: Mutex::new(HashSet::new()),
+        };
+        IndexWriter {
+            analyzer: Arc::new(analyzer),
+            pending: Segment::default(),
+            pending_deletes: HashSet::new(),
+            shared: Arc::new(shared),
+            merge_policy: Box::new(TieredMergePolicy::default()),
+            merge_threads: Vec::new(),
+            next_segment_id: 0,
+        }
+    }
+
+    /// Replaces the policy used to select segments for merging.
+    pub fn set_merge_policy<P>(&mut self, policy: P)
+    where
+        P: MergePolicy + 'static,
+    {
+        self.merge_policy = Box::new(policy);
+    }
+
+    /// Creates a reader sharing the data published by this writer.
+    pub fn reader(&self) -> IndexReader {
+        IndexReader {
+            analyzer: Arc::clone(&self.analyzer),
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    /// Index document, it becomes searchable after the next commit.
+    /// Terms of all fields are searchable, fields are not scored.
+    /// A document with an already added id replaces its previous version on commit.
+    pub fn add(&mut self, docs: &[Document]) {
+        for doc in docs.iter() {
+            self.delete(doc.id);
+            let analyzer = &self.analyzer;
+            let tokens = doc
+                .field_texts()
+                .flat_map(|(_, text)| analyzer.analyze(text));
+            self.pending.add(doc.id, tokens);
+        }
+    }
+
+    /// Deletes document, it disappears from search results after the next commit.
+    /// Documents added before the deletion are deleted too, even if they are not committed yet.
+    pub fn delete(&mut self, id: u64) {
+        self.pending.remove(id);
+        self.pending_deletes.insert(id);
+    }
+
+    /// Publishes all added and deleted documents to readers as a new segment,
+    /// then starts merges selected by the merge policy.
+    /// Searchers opened before the commit keep seeing the previous state.
+    pub fn commit(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        let deletes = std::mem::take(&mut self.pending_deletes);
+        let id = self.next_segment_id;
+        self.next_segment_id += 1;
+        self.shared.update(|segments| {
+            for entry in segments.iter_mut() {
+                entry.delete(&deletes);
+            }
+            if !pending.is_empty() {
+                segments.push(SegmentEntry::new(id, pending));
+            }
+        });
+        self.merge();
+    }
+
+    /// Blocks until all running merges are finished and published,
+    /// including merges of segments produced by them.
+    pub fn wait_merging_threads(&mut self) {
+        while !self.merge_threads.is_empty() {
+            for handle in self.merge_threads.drain(..) {
+                if let Err(panic) = handle.join() {
+                    std::panic::resume_unwind(panic);
+                }
+            }
+            self.merge();
+        }
+    }
+
+    /// Starts a background merge for every group of segments selected by the merge policy.
+    /// Segments which are already being merged are not offered to the policy.
+    fn merge(&mut self) {
+        self.merge_threads.retain(|handle| !handle.is_finished());
+        // The snapshot is read under the lock, so segments of a merge finished meanwhile
+        // are either still marked as merging or already replaced by the merged segment.
+        let mut merging = self
+            .shared
+            .merging
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let snapshot = self.shared.snapshot();
+        let candidates: Vec<&SegmentEntry> = snapshot
+            .segments
+            .iter()
+            .filter(|entry| !merging.contains(&entry.id))
+            .collect();
+        let infos: Vec<SegmentInfo> = candidates.iter().map(|entry| entry.info()).collect();
+
+        for group in self.merge_policy.merges(&infos) {
+            let sources: Vec<SegmentEntry> = group
+                .iter()
+                .filter_map(|&i| candidates.get(i))
+                .map(|&entry| entry.clone())
+                .collect();
+            if sources.is_empty() || sources.iter().any(|entry| merging.contains(&entry.id)) {
+                continue;
+            }
+            merging.extend(sources.iter().map(|entry| entry.id));
+
+            let id = self.next_segment_id;
+            self.next_segment_id += 1;
+            let shared = Arc::clone(&self.shared);
+            self.merge_threads.push(thread::spawn(move || {
+                let merged = Segment::merge(&sources);
+                shared.finish_merge(id, merged, &sources);
+            }));
+        }
+    }
+}
+
+impl Shared {
+    fn snapshot(&self) -> Arc<Snapshot> {
+        let published = self
+            .published
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        Arc::clone(&published)
+    }
+
+    /// Publishes a new snapshot with the segment list modified by `f`.
+    fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut Vec<SegmentEntry>),
+    {
+        let mut published = self
+            .published
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut segments = published.segments.clone();
+        f(&mut segments);
+        *published = Arc::new(Snapshot { segments });
+    }
+
+    /// Replaces merged segments with the result of their merge.
+    /// The merge is dropped if any of its segments is no longer published,
+    /// so documents are never published twice.
+    fn finish_merge(&self, id: u64, merged: Segment, sources: &[SegmentEntry]) {
+        let ids: HashSet<u64> = sources.iter().map(|entry| entry.id).collect();
+        {
+            let mut published = self
+                .published
+                .write()
+                .unwrap_or_else(PoisonError::into_inner);
+            let current: Vec<&SegmentEntry> = published
+                .segments
+                .iter()
+                .filter(|entry| ids.contains(&entry.id))
+                .collect();
+            if current.len() == ids.len() {
+                let position = published
+                    .segments
+                    .iter()
+                    .position(|entry| ids.contains(&entry.id));
+                let entry = SegmentEntry::merged(id, merged, &current);
+                let mut segments: Vec<SegmentEntry> = published
+                    .segments
+                    .iter()
+                    .filter(|entry| !ids.contains(&entry.id))
+                    .cloned()
+                    .collect();
+                if entry.info().num_docs > 0 {
+                    segments.insert(position.unwrap_or(segments.len()), entry);
+                }
+                *published = Arc::new(Snapshot { segments });
+            }
+        }
+        let mut merging = self.merging.lock().unwrap_or_else(PoisonError::into_inner);
+        merging.retain(|id| !ids.contains(id));
+    }
+}
+
+impl IndexReader {
+    /// Opens a searcher over the last committed state.
+    pub fn searcher(&self) -> Searcher {
+        Searcher {
+            analyzer: Arc::clone(&self.analyzer),
+            snapshot: self.shared.snapshot(),
+        }
+    }
+}
+
+impl Searcher {
+    /// Search text in the snapshot.
+    /// Returns ids of matched documents ordered by doc id.
+    pub fn search(&self, text: &str) -> Vec<u64> {
+        ordered(matches(&self.analyzer, text, |token| {
+            let ids: HashSet<u64> = self
+                .snapshot
+                .segments
+                .iter()
+                .flat_map(|entry| entry.postings(token))
+                .collect();
+            (!ids.is_empty()).then_some(Cow::Owned(ids))
+        }))
+    }
+
+    /// Search text in the snapshot and return only the requested page of results.
+    pub fn search_page(&self, text: &str, page: &Page) -> Vec<u64> {
+        page.apply(self.search(text))
+    }
+
+    /// Number of documents visible to the searcher.
+    pub fn num_docs(&self) -> usize {
+        self.snapshot
+            .segments
+            .iter()
+            .map(|entry| entry.info().num_live())
+            .sum()
+    }
+
+    /// Number of segments visible to the searcher.
+    pub fn num_segments(&self) -> usize {
+        self.snapshot.segments.len()
+    }
+}
+
+#[cfg(test)]
+mod writer_tests {
+    use crate::index::Document;
+    use crate::merge_policy::{LogSizeMergePolicy, NoMergePolicy};
+    use crate::segment::Segment;
+    use crate::test_support::fox_docs;
+    use crate::writer::IndexWriter;
+    use std::thread;
+
+    #[test]
+    fn uncommitted_docs_are_invisible_test() {
+        let mut writer = IndexWriter::default();
+        let reader = writer.reader();
+        writer.add(&fox_docs());
+        assert!(
+            reader.searcher().search("brown foxes").is_empty(),
+            "uncommitted documents are visible"
+        );
+        writer.commit();
+        assert_eq!(
+            reader.searcher().search("brown foxes"),
+            [1, 2],
+            "committed documents are invisible"
+        );
+    }
+
+    #[test]
+    fn searcher_snapshot_test() {
+        let mut writer = IndexWriter::default();
+        let reader = writer.reader();
+        let [first, second] = fox_docs();
+        writer.add(&[first]);
+        writer.commit();
+        let searcher = reader.searcher();
+        writer.add(&[second]);
+        writer.commit();
+        assert_eq!(
+            searcher.search("lazy"),
+            [1],
+            "snapshot changed after commit"
+        );
+        assert_eq!(reader.searcher().search("lazy"), [1, 2], "commit is lost");
+    }
+
+    #[test]
+    fn concurrent_readers_test() {
+        let mut writer = IndexWriter::default();
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let reader = writer.reader();
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        let found = reader.searcher().search("lazy");
+                        assert!(found.is_empty() || found == [1, 2], "torn snapshot");
+                    }
+                })
+            })
+            .collect();
+        writer.add(&fox_docs());
+        writer.commit();
+        for reader in readers {
+            reader.join().expect("reader thread panicked");
+        }
+    }
+
+    #[test]
+    fn delete_test() {
+        let mut writer = IndexWriter::default();
+        let reader = writer.reader();
+        writer.add(&fox_docs());
+        writer.commit();
+        writer.delete(1);
+        assert_eq!(
+            reader.searcher().search("lazy"),
+            [1, 2],
+            "delete before commit"
+        );
+        writer.commit();
+        assert_eq!(reader.searcher().search("lazy"), [2], "delete failed");
+        assert_eq!(reader.searcher().num_docs(), 1, "deleted doc is counted");
+    }
+
+    #[test]
+    fn re_add_test() {
+        let mut writer = IndexWriter::default();
+        let reader = writer.reader();
+        writer.add(&[Document::new(1, "brown fox")]);
+        writer.commit();
+        writer.add(&[Document::new(1, "lazy dog"), Document::new(1, "lazy fox")]);
+        assert_eq!(
+            reader.searcher().search("fox"),
+            [1],
+            "re-added document is visible"
+        );
+        writer.commit();
+        let searcher = reader.searcher();
+        assert!(
+            searcher.search("brown").is_empty(),
+            "previous version matched"
+        );
+        assert!(
+            searcher.search("dog").is_empty(),
+            "earlier version in batch matched"
+        );
+        assert_eq!(
+            searcher.search("lazy fox"),
+            [1],
+            "re-added document missing"
+        );
+        assert_eq!(searcher.num_docs(), 1, "re-added document counted twice");
+    }
+
+    #[test]
+    fn delete_uncommitted_test() {
+        let mut writer = IndexWriter::default();
+        let reader = writer.reader();
+        writer.add(&fox_docs());
+        writer.delete(2);
+        writer.commit();
+        assert_eq!(
+            reader.searcher().search("lazy"),
+            [1],
+            "uncommitted delete failed"
+        );
+    }
+
+    #[test]
+    fn segment_per_commit_test() {
+        let mut writer = IndexWriter::default();
+        writer.set_merge_policy(NoMergePolicy);
+        let reader = writer.reader();
+        for doc in fox_docs() {
+            writer.add(&[doc]);
+            writer.commit();
+        }
+        let searcher = reader.searcher();
+        assert_eq!(
+            searcher.num_segments(),
+            2,
+            "commit did not create a segment"
+        );
+        assert_eq!(
+            searcher.search("brown foxes"),
+            [1, 2],
+            "search over segments failed"
+        );
+    }
+
+    #[test]
+    fn merge_test() {
+        let mut writer = IndexWriter::default();
+        writer.set_merge_policy(LogSizeMergePolicy {
+            merge_factor: 2,
+            min_merge_docs: 1,
+        });
+        let reader = writer.reader();
+        for id in 0..8 {
+            writer.add(&[Document::new(
+                id,
+                "Quick brown foxes leap over lazy dogs in summer",
+            )]);
+            writer.commit();
+            writer.wait_merging_threads();
+        }
+        let searcher = reader.searcher();
+        assert_eq!(searcher.num_segments(), 1, "segments are not merged");
+        assert_eq!(searcher.search("lazy dogs"), (0..8).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn stale_merge_is_dropped_test() {
+        let mut writer = IndexWriter::default();
+        writer.set_merge_policy(NoMergePolicy);
+        let reader = writer.reader();
+        for doc in fox_docs() {
+            writer.add(&[doc]);
+            writer.commit();
+        }
+        let sources = writer.shared.snapshot().segments.clone();
+        writer
+            .shared
+            .finish_merge(10, Segment::merge(&sources), &sources);
+        writer
+            .shared
+            .finish_merge(11, Segment::merge(&sources), &sources);
+        let searcher = reader.searcher();
+        assert_eq!(searcher.num_segments(), 1, "segments are not merged");
+        assert_eq!(
+            searcher.num_docs(),
+            2,
+            "merged segments are published twice"
+        );
+    }
+
+    #[test]
+    fn merge_purges_deleted_test() {
+        let mut writer = IndexWriter::default();
+        writer.set_merge_policy(NoMergePolicy);
+        let reader = writer.reader();
+        writer.add(&fox_docs());
+        writer.commit();
+        writer.delete(1);
+        writer.set_merge_policy(LogSizeMergePolicy {
+            merge_factor: 2,
+            min_merge_docs: 1,
+        });
+        writer.add(&[Document::new(3, "A lazy afternoon")]);
+        writer.commit();
+        writer.wait_merging_threads();
+        let searcher = reader.searcher();
+        assert_eq!(searcher.num_segments(), 1, "segments are not merged");
+        assert_eq!(searcher.snapshot.segments[0].info().num_deleted, 0);
+        assert_eq!(
+            searcher.search("lazy"),
+            [2, 3],
+            "deleted doc survived merge"
+        );
+    }
+}